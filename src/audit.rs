@@ -0,0 +1,200 @@
+//! The [`AuditLog`] struct records the environment variables read to set up the configuration.
+
+use crate::secret::{is_secret_var, MASK};
+use crate::source;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::env::VarError;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+thread_local! {
+    /// Variables read in the current thread while an audit is in progress.
+    static RECORDER: RefCell<Option<Vec<AuditEntry>>> = const { RefCell::new(None) };
+    /// Prefix of the variables read when the canonical ones are not set,
+    /// see [`with_fallback_prefix()`].
+    static FALLBACK_PREFIX: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// An environment variable read during the configuration set-up.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditEntry {
+    /// The variable name, e.g. `PORT`.
+    pub name: String,
+    /// Whether the variable was set or not.
+    pub set: bool,
+    /// The value read if the variable was set, otherwise the default value
+    /// used if known. Values of sensitive variables are masked.
+    pub value: Option<String>,
+}
+
+/// All the environment variables read during the configuration set-up,
+/// in the order they were read, including the ones not set.
+/// See [`Config::init_audited()`](crate::Config::init_audited).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AuditLog {
+    /// The variables read, in the order they were read.
+    pub entries: Vec<AuditEntry>,
+}
+
+impl AuditLog {
+    /// Get the entry of the variable passed, if it was read.
+    pub fn get(&self, name: &str) -> Option<&AuditEntry> {
+        self.entries.iter().find(|e| e.name == name)
+    }
+
+    /// Set the default value of the variables not set, taking the values
+    /// from the map of defaults passed, by variable name.
+    pub(crate) fn fill_defaults(&mut self, defaults: &BTreeMap<String, String>) {
+        for entry in self.entries.iter_mut().filter(|e| !e.set) {
+            if let Some(value) = defaults.get(&entry.name) {
+                let value = if is_secret_var(&entry.name) { MASK } else { value };
+                entry.value = Some(value.to_string());
+            }
+        }
+    }
+}
+
+impl Display for AuditLog {
+    /// Prints out each variable read in `.env` format, with a comment
+    /// telling whether the value was set or it's the default.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (i, entry) in self.entries.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(
+                f,
+                "{}={} # {}",
+                entry.name,
+                entry.value.as_deref().unwrap_or_default(),
+                if entry.set { "set" } else { "not set" },
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Run the function passed recording the environment variables read by the crate
+/// in the current thread, e.g. to audit the configuration set-up together
+/// with the variables read with [`Config::env_or()`](crate::Config::env_or).
+///
+/// # Examples
+/// ```
+/// use std::env;
+/// use server_env_config::Config;
+/// use server_env_config::audit::audited;
+///
+/// env::set_var("WORKERS", "8");
+/// let (result, audit) = audited(|| -> anyhow::Result<(Config, u32, u64)> {
+///     let config = Config::init(8080)?;
+///     let workers = Config::env_or("WORKERS", 4)?;
+///     let queue_size = Config::env_or("QUEUE_SIZE", 100)?;
+///     Ok((config, workers, queue_size))
+/// });
+/// let (_, workers, queue_size) = result.unwrap();
+/// assert_eq!((workers, queue_size), (8, 100));
+/// assert!(audit.get("PORT").is_some());
+/// assert_eq!(audit.get("WORKERS").unwrap().value.as_deref(), Some("8"));
+/// let queue_size = audit.get("QUEUE_SIZE").unwrap();
+/// assert!(!queue_size.set);
+/// assert_eq!(queue_size.value.as_deref(), Some("100"));
+/// ```
+pub fn audited<T>(f: impl FnOnce() -> T) -> (T, AuditLog) {
+    start();
+    let result = f();
+    (result, stop())
+}
+
+/// Start recording the variables read in the current thread.
+pub(crate) fn start() {
+    RECORDER.with(|r| *r.borrow_mut() = Some(Vec::new()));
+}
+
+/// Stop recording the variables read in the current thread,
+/// returning the ones read since [`start()`] was called.
+pub(crate) fn stop() -> AuditLog {
+    let entries = RECORDER.with(|r| r.borrow_mut().take()).unwrap_or_default();
+    AuditLog { entries }
+}
+
+/// Run the function passed reading each variable with the `prefix` passed
+/// prepended to its name (e.g. `RENDER_PORT`) when the variable is not set.
+/// The previous prefix is restored when `f` returns, or if it panics.
+pub(crate) fn with_fallback_prefix<T>(prefix: &str, f: impl FnOnce() -> T) -> T {
+    let _guard = RestorePrefix(FALLBACK_PREFIX.with(|p| p.replace(Some(prefix.to_string()))));
+    f()
+}
+
+/// Restores the fallback prefix it holds when dropped, see [`with_fallback_prefix()`].
+struct RestorePrefix(Option<String>);
+
+impl Drop for RestorePrefix {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        FALLBACK_PREFIX.with(|p| *p.borrow_mut() = previous);
+    }
+}
+
+/// Same as [`std::env::var()`], but reading the variable from the current
+/// [`source`](crate::source), the process environment by default, recording the variable
+/// read if an audit is in progress in the current thread, and
+/// reading the prefixed variable if a fallback prefix is set and
+/// the variable is not (see [`with_fallback_prefix()`]). Empty values
+/// are not present if [`set_empty_as_unset()`](crate::set_empty_as_unset)
+/// is on. With the `keyring` feature the value is taken from the OS keyring
+/// if the `{key}_KEYRING` marker is set, see [`keyring`](crate::keyring).
+pub(crate) fn var(key: &str) -> Result<String, VarError> {
+    #[cfg(feature = "keyring")]
+    let from_keyring = crate::keyring::lookup(key);
+    #[cfg(not(feature = "keyring"))]
+    let from_keyring: Option<String> = None;
+    let masked = from_keyring.is_some() || is_secret_var(key);
+    let result = match from_keyring {
+        Some(value) => Ok(value),
+        None => read(key).or_else(|err| {
+            FALLBACK_PREFIX.with(|p| match p.borrow().as_deref() {
+                Some(prefix) => read(&format!("{prefix}{key}")),
+                None => Err(err),
+            })
+        }),
+    };
+    RECORDER.with(|r| {
+        if let Some(entries) = r.borrow_mut().as_mut() {
+            if entries.iter().any(|e| e.name == key) {
+                return;
+            }
+            let value = result.as_ref().ok().map(|v| {
+                if masked {
+                    MASK.to_string()
+                } else {
+                    v.clone()
+                }
+            });
+            entries.push(AuditEntry { name: key.to_string(), set: result.is_ok(), value });
+        }
+    });
+    result
+}
+
+/// Get the variable from the current [`source`](crate::source), with the empty
+/// values not present if [`set_empty_as_unset()`](crate::set_empty_as_unset) is on.
+fn read(key: &str) -> Result<String, VarError> {
+    match source::get(key) {
+        Some(value) if value.is_empty() && crate::empty_as_unset() => Err(VarError::NotPresent),
+        Some(value) => Ok(value),
+        None => Err(VarError::NotPresent),
+    }
+}
+
+/// Record the default value used for the variable passed if it was read
+/// and not set, while an audit is in progress in the current thread.
+pub(crate) fn record_default(key: &str, default_value: &str) {
+    RECORDER.with(|r| {
+        if let Some(entries) = r.borrow_mut().as_mut() {
+            for entry in entries.iter_mut().filter(|e| e.name == key && !e.set && e.value.is_none()) {
+                entry.value = Some(if is_secret_var(key) { MASK.to_string() } else { default_value.to_string() });
+            }
+        }
+    });
+}
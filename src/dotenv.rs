@@ -0,0 +1,313 @@
+//! Functions to read environment variables from `.env` files.
+
+use crate::secret::{is_secret_var, MASK};
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::Path;
+
+/// A difference found by [`diff_env_files()`] between two `.env` files,
+/// with the values of sensitive variables masked.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EnvDiff {
+    /// The variable is set in both files, with different values.
+    Changed {
+        /// The variable name.
+        name: String,
+        /// The value in the first file.
+        a: String,
+        /// The value in the second file.
+        b: String,
+    },
+    /// The variable is set in the first file, but missing in the second.
+    Missing {
+        /// The variable name.
+        name: String,
+        /// The value in the first file.
+        value: String,
+    },
+    /// The variable is not set in the first file, but it's in the second.
+    Extra {
+        /// The variable name.
+        name: String,
+        /// The value in the second file.
+        value: String,
+    },
+}
+
+impl EnvDiff {
+    /// Get the name of the variable that differs.
+    pub fn name(&self) -> &str {
+        match self {
+            EnvDiff::Changed { name, .. } | EnvDiff::Missing { name, .. } | EnvDiff::Extra { name, .. } => name,
+        }
+    }
+}
+
+impl Display for EnvDiff {
+    /// Prints out the difference in a diff-like format: `~ NAME: "a" -> "b"`
+    /// for changed values, `- NAME="a"` for missing variables
+    /// and `+ NAME="b"` for extra variables.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvDiff::Changed { name, a, b } => write!(f, "~ {name}: \"{a}\" -> \"{b}\""),
+            EnvDiff::Missing { name, value } => write!(f, "- {name}=\"{value}\""),
+            EnvDiff::Extra { name, value } => write!(f, "+ {name}=\"{value}\""),
+        }
+    }
+}
+
+/// Parse the content of a `.env` file, returning the variables found
+/// in order. Each line has the form `KEY=VALUE`, where the value may be
+/// enclosed in double or single quotes. Empty lines and lines starting
+/// with `#` are ignored, as the comments after an unquoted value.
+/// Lines may start with `export `, so the file can also be sourced in a shell.
+///
+/// A leading UTF-8 BOM is skipped, and the lines may end with CRLF (`\r\n`)
+/// or CR alone, like in files created on Windows or exported by some tools.
+/// # Examples
+/// ```
+/// use server_env_config::dotenv;
+///
+/// let vars = dotenv::parse(r#"
+/// ## Server
+/// PORT=8080
+/// APP_URI="api/v1"
+/// HOST='0'  # all interfaces
+/// "#).unwrap();
+/// assert_eq!(vars, vec![
+///     ("PORT".to_string(), "8080".to_string()),
+///     ("APP_URI".to_string(), "api/v1".to_string()),
+///     ("HOST".to_string(), "0".to_string()),
+/// ]);
+///
+/// // Lines with "export" prefix
+/// let vars = dotenv::parse("export PORT=8080\nHOST=0\nexport  APP_URI=api").unwrap();
+/// assert_eq!(vars, vec![
+///     ("PORT".to_string(), "8080".to_string()),
+///     ("HOST".to_string(), "0".to_string()),
+///     ("APP_URI".to_string(), "api".to_string()),
+/// ]);
+///
+/// // BOM and CRLF line endings
+/// let vars = dotenv::parse("\u{feff}PORT=8080\r\nAPP_URI=\"api\"\r\n").unwrap();
+/// assert_eq!(vars, vec![
+///     ("PORT".to_string(), "8080".to_string()),
+///     ("APP_URI".to_string(), "api".to_string()),
+/// ]);
+///
+/// // Quotes inside the values
+/// let vars = dotenv::parse(r#"Q1='say "hi"'
+/// Q2="it's \"ok\""
+/// Q3="C:\\apps\\"
+/// Q4="C:\apps""#).unwrap();
+/// assert_eq!(vars, vec![
+///     ("Q1".to_string(), "say \"hi\"".to_string()),
+///     ("Q2".to_string(), "it's \"ok\"".to_string()),
+///     ("Q3".to_string(), "C:\\apps\\".to_string()),
+///     ("Q4".to_string(), "C:\\apps".to_string()),
+/// ]);
+///
+/// assert!(dotenv::parse("NOT A VARIABLE").is_err());
+/// ```
+pub fn parse(content: &str) -> Result<Vec<(String, String)>> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let content = content.replace("\r\n", "\n").replace('\r', "\n");
+    let mut vars = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = match line.strip_prefix("export ") {
+            Some(rest) => rest.trim_start(),
+            None => line,
+        };
+        let (key, value) = match line.split_once('=') {
+            Some((key, value)) if is_valid_key(key.trim()) => (key.trim(), value.trim()),
+            _ => bail!("Invalid .env line {}: \"{line}\"", i + 1),
+        };
+        vars.push((key.to_string(), parse_value(value)));
+    }
+    Ok(vars)
+}
+
+/// Read the `.env` file at `path`, and set the variables found in the
+/// process environment, unless they are already set.
+/// # Examples
+/// ```
+/// use std::env;
+/// use std::path::Path;
+/// use server_env_config::dotenv;
+///
+/// let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+///
+/// // File starting with a UTF-8 BOM
+/// dotenv::load(fixtures.join("bom.env")).unwrap();
+/// assert_eq!(env::var("BOM_APP_NAME").unwrap(), "my-api");
+/// assert_eq!(env::var("BOM_PORT").unwrap(), "8080");
+///
+/// // File with CRLF line endings
+/// dotenv::load(fixtures.join("crlf.env")).unwrap();
+/// assert_eq!(env::var("CRLF_APP_NAME").unwrap(), "my-api");
+/// assert_eq!(env::var("CRLF_HOST").unwrap(), "0");
+/// assert_eq!(env::var("CRLF_PORT").unwrap(), "8080");
+/// ```
+pub fn load(path: impl AsRef<Path>) -> Result<()> {
+    for (key, value) in read(path)? {
+        if env::var_os(&key).is_none() {
+            env::set_var(key, value);
+        }
+    }
+    Ok(())
+}
+
+/// Read the `.env` file at `path`, and set the variables found in the
+/// process environment, overriding the ones already set.
+/// # Examples
+/// ```
+/// use std::env;
+/// use std::fs;
+/// use server_env_config::dotenv;
+///
+/// let path = env::temp_dir().join("server_env_config_load_override.env");
+/// fs::write(&path, "DOTENV_VAR=from file\n").unwrap();
+/// env::set_var("DOTENV_VAR", "from env");
+///
+/// dotenv::load(&path).unwrap();
+/// assert_eq!(env::var("DOTENV_VAR").unwrap(), "from env");
+/// dotenv::load_override(&path).unwrap();
+/// assert_eq!(env::var("DOTENV_VAR").unwrap(), "from file");
+/// # fs::remove_file(&path).unwrap();
+/// ```
+pub fn load_override(path: impl AsRef<Path>) -> Result<()> {
+    for (key, value) in read(path)? {
+        env::set_var(key, value);
+    }
+    Ok(())
+}
+
+/// Compare the `.env` files at `a` and `b`, e.g. the ones of two environments
+/// to audit config drift, returning the variables with different values,
+/// the ones missing in `b` and the extra ones in `b`, in the order they
+/// appear in `a`, followed by the extra ones in the order they appear in `b`.
+/// Files that only differ in quotes, comments or order have no differences.
+///
+/// The values of sensitive variables, like `DATABASE_URL` or the ones with
+/// `PASSWORD` or `TOKEN` in their names, are masked, but still compared.
+/// If a variable is set more than once in a file, the last value is used.
+/// # Examples
+/// ```
+/// use std::path::Path;
+/// use server_env_config::dotenv::{diff_env_files, EnvDiff};
+///
+/// let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+/// let diffs = diff_env_files(&fixtures.join("stage.env"), &fixtures.join("production.env")).unwrap();
+/// assert_eq!(diffs, vec![
+///     EnvDiff::Changed { name: "APP_ENV".to_string(), a: "stage".to_string(), b: "production".to_string() },
+///     EnvDiff::Missing { name: "LOG_FORMAT".to_string(), value: "json".to_string() },
+///     EnvDiff::Changed { name: "DATABASE_URL".to_string(), a: "********".to_string(), b: "********".to_string() },
+///     EnvDiff::Changed { name: "MAX_CONNECTIONS".to_string(), a: "10".to_string(), b: "50".to_string() },
+///     EnvDiff::Extra { name: "ALLOWED_HOSTS".to_string(), value: "example.com".to_string() },
+/// ]);
+/// let report: Vec<String> = diffs.iter().map(|d| d.to_string()).collect();
+/// assert_eq!(report, vec![
+///     "~ APP_ENV: \"stage\" -> \"production\"",
+///     "- LOG_FORMAT=\"json\"",
+///     "~ DATABASE_URL: \"********\" -> \"********\"",
+///     "~ MAX_CONNECTIONS: \"10\" -> \"50\"",
+///     "+ ALLOWED_HOSTS=\"example.com\"",
+/// ]);
+///
+/// // No differences with itself
+/// assert!(diff_env_files(&fixtures.join("stage.env"), &fixtures.join("stage.env")).unwrap().is_empty());
+///
+/// let err = diff_env_files(&fixtures.join("stage.env"), &fixtures.join("not-found.env")).unwrap_err();
+/// assert!(err.to_string().contains("not-found.env"));
+/// ```
+pub fn diff_env_files(a: &Path, b: &Path) -> Result<Vec<EnvDiff>> {
+    let (a, b) = (read(a)?, read(b)?);
+    let a_map: HashMap<&str, &str> = a.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+    let b_map: HashMap<&str, &str> = b.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+    let mask = |name: &str, value: &str| {
+        if is_secret_var(name) { MASK.to_string() } else { value.to_string() }
+    };
+    let mut diffs = Vec::new();
+    for (i, (name, _)) in a.iter().enumerate() {
+        if a[..i].iter().any(|(n, _)| n == name) {
+            continue;
+        }
+        let a_value = a_map[name.as_str()];
+        match b_map.get(name.as_str()) {
+            None => diffs.push(EnvDiff::Missing { name: name.clone(), value: mask(name, a_value) }),
+            Some(b_value) if *b_value != a_value => diffs.push(EnvDiff::Changed {
+                name: name.clone(),
+                a: mask(name, a_value),
+                b: mask(name, b_value),
+            }),
+            Some(_) => {}
+        }
+    }
+    for (i, (name, _)) in b.iter().enumerate() {
+        if !a_map.contains_key(name.as_str()) && !b[..i].iter().any(|(n, _)| n == name) {
+            diffs.push(EnvDiff::Extra { name: name.clone(), value: mask(name, b_map[name.as_str()]) });
+        }
+    }
+    Ok(diffs)
+}
+
+/// Read and parse the `.env` file at `path`.
+fn read(path: impl AsRef<Path>) -> Result<Vec<(String, String)>> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read .env file \"{}\"", path.display()))?;
+    parse(&content).with_context(|| format!("Failed to parse .env file \"{}\"", path.display()))
+}
+
+fn is_valid_key(key: &str) -> bool {
+    !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Unescape the double quoted value passed, without the opening quote,
+/// up to the closing `"`, or `None` if it's not closed. `\"` is read as `"`
+/// and `\\` as `\`, other backslashes are kept as they are.
+fn unescape_quoted(value: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => return Some(unescaped),
+            '\\' => match chars.clone().next() {
+                Some(next @ ('"' | '\\')) => {
+                    unescaped.push(next);
+                    chars.next();
+                }
+                _ => unescaped.push(c),
+            },
+            _ => unescaped.push(c),
+        }
+    }
+    None
+}
+
+/// Remove the quotes around the value, or the comment after an unquoted value.
+/// In double quoted values `\"` is read as `"` and `\\` as `\`.
+fn parse_value(value: &str) -> String {
+    if let Some(rest) = value.strip_prefix('"') {
+        if let Some(unescaped) = unescape_quoted(rest) {
+            return unescaped;
+        }
+    }
+    if let Some(rest) = value.strip_prefix('\'') {
+        if let Some(end) = rest.find('\'') {
+            return rest[..end].to_string();
+        }
+    }
+    match value.find(" #") {
+        Some(comment) => value[..comment].trim_end().to_string(),
+        None => value.to_string(),
+    }
+}
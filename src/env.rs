@@ -1,13 +1,50 @@
 //! The [`Environment`] enum represents possible deployment environments for an application.
 
-use anyhow::{Context, Result};
-use std::env;
+use crate::audit::var;
+use crate::lint::edit_distance;
+use crate::vars::{APP_ENV, KUBERNETES_SERVICE_HOST};
+use crate::FromEnv;
+use anyhow::{anyhow, bail, Context, Error, Result};
+use log::warn;
+use std::collections::HashMap;
 use std::fmt::Debug;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
 use std::str::FromStr;
-use strum_macros::{Display, EnumString};
+use std::sync::OnceLock;
+use strum::VariantNames;
+use strum_macros::{Display, EnumVariantNames};
+
+/// Names accepted for each environment, see the [`FromStr`] implementation.
+const NAMES: [(&str, Environment); 9] = [
+    ("local", Environment::Local),
+    ("dev", Environment::Local),
+    ("development", Environment::Local),
+    ("test", Environment::Test),
+    ("testing", Environment::Test),
+    ("stage", Environment::Stage),
+    ("staging", Environment::Stage),
+    ("production", Environment::Production),
+    ("prod", Environment::Production),
+];
+
+/// Custom environment aliases, see [`Environment::register_aliases()`].
+static ALIASES: OnceLock<HashMap<String, Environment>> = OnceLock::new();
 
 /// Possible deployment environments for an application.
-#[derive(Debug, Default, Display, PartialEq, EnumString, Clone)]
+///
+/// Environments are ordered by increasing "realness": `Local < Test < Stage < Production`,
+/// so comparisons can be used to gate strict behavior.
+/// # Examples
+/// ```
+/// use server_env_config::env::Environment;
+///
+/// assert!(Environment::Production > Environment::Local);
+/// assert!(Environment::Stage >= Environment::Stage);
+/// assert!(Environment::Test < Environment::Stage);
+/// ```
+#[derive(Debug, Default, Display, PartialEq, Eq, PartialOrd, Ord, EnumVariantNames, Clone)]
 #[strum(serialize_all = "snake_case")]
 pub enum Environment {
     #[default]
@@ -18,7 +55,8 @@ pub enum Environment {
 }
 
 impl Environment {
-    /// Get the value from the environment variable `APP_ENV`.
+    /// Get the value from the environment variable `APP_ENV`, parsed with
+    /// the [`FromStr`] implementation, so case insensitive and accepting aliases.
     /// It raises an error if the string doesn't match a possible environment.
     /// # Examples
     /// ```
@@ -29,15 +67,253 @@ impl Environment {
     /// assert!(matches!(Environment::init(), Ok(Environment::Production)));
     /// env::set_var("APP_ENV", "Not a environment");
     /// assert!(Environment::init().is_err());
+    /// env::set_var("APP_ENV", "stagging");
+    /// let err = Environment::init().unwrap_err();
+    /// assert!(format!("{err:#}").contains("did you mean \"staging\"?"));
     /// env::remove_var("APP_ENV"); // if not set, local environment is the default
     /// assert!(matches!(Environment::init(), Ok(Environment::Local)));
     /// ```
     pub fn init() -> Result<Self> {
-        let app_env = env::var("APP_ENV");
+        let app_env = var(APP_ENV);
         match app_env {
             Err(_) => Ok(Environment::default()),
             Ok(env) => Environment::from_str(env.as_str())
                 .with_context(|| format!("APP_ENV invalid value \"{env}\"")),
         }
     }
+
+    /// Same as [`Environment::init()`], but reading first the environment name
+    /// from the file at `path`, e.g. `/etc/app/environment` written when the
+    /// image is built, for immutable-image deploys.
+    ///
+    /// The precedence is: the file contents, trimmed and parsed with the
+    /// [`FromStr`] implementation (so case insensitive and accepting aliases),
+    /// then the `APP_ENV` env variable, then the default environment.
+    /// The file is skipped only if it doesn't exist: an error is raised if
+    /// it can't be read or the name is not valid, so a broken image
+    /// doesn't silently start with another environment.
+    /// # Examples
+    /// ```
+    /// use std::{env, fs};
+    /// use server_env_config::env::Environment;
+    ///
+    /// let path = env::temp_dir().join("server_env_config_init_from_file");
+    /// env::set_var("APP_ENV", "stage");
+    ///
+    /// // File present, it takes precedence
+    /// fs::write(&path, "Production\n").unwrap();
+    /// assert_eq!(Environment::init_from_file(&path).unwrap(), Environment::Production);
+    /// fs::write(&path, "prod").unwrap();
+    /// assert_eq!(Environment::init_from_file(&path).unwrap(), Environment::Production);
+    /// fs::write(&path, "qa").unwrap();
+    /// let err = Environment::init_from_file(&path).unwrap_err();
+    /// assert!(format!("{err:#}").contains("invalid environment \"qa\""));
+    ///
+    /// // File absent, APP_ENV is used
+    /// fs::remove_file(&path).unwrap();
+    /// assert_eq!(Environment::init_from_file(&path).unwrap(), Environment::Stage);
+    ///
+    /// // Both absent, the default is used
+    /// env::remove_var("APP_ENV");
+    /// assert_eq!(Environment::init_from_file(&path).unwrap(), Environment::Local);
+    /// ```
+    pub fn init_from_file(path: &Path) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(name) => Environment::from_str(&name)
+                .with_context(|| format!("{} invalid value \"{}\"", path.display(), name.trim())),
+            Err(e) if e.kind() == ErrorKind::NotFound => Environment::init(),
+            Err(e) => Err(e).with_context(|| format!("Failed to read environment file \"{}\"", path.display())),
+        }
+    }
+
+    /// Parse the environment name passed like the [`FromStr`] implementation,
+    /// but falling back to the default environment (see [`Environment::default()`]),
+    /// logging a warning, if the value is not recognized.
+    /// # Examples
+    /// ```
+    /// use std::sync::Mutex;
+    /// use log::{Log, Metadata, Record};
+    /// use server_env_config::env::Environment;
+    ///
+    /// // Logger that keeps the messages logged
+    /// struct TestLogger(Mutex<Vec<String>>);
+    /// impl Log for TestLogger {
+    ///     fn enabled(&self, _: &Metadata) -> bool { true }
+    ///     fn log(&self, record: &Record) { self.0.lock().unwrap().push(record.args().to_string()) }
+    ///     fn flush(&self) {}
+    /// }
+    /// static LOGGER: TestLogger = TestLogger(Mutex::new(Vec::new()));
+    /// log::set_logger(&LOGGER).unwrap();
+    /// log::set_max_level(log::LevelFilter::Warn);
+    ///
+    /// assert_eq!(Environment::parse_or_default("Production"), Environment::Production);
+    /// assert!(LOGGER.0.lock().unwrap().is_empty());
+    ///
+    /// assert_eq!(Environment::parse_or_default("qa"), Environment::Local);
+    /// assert!(LOGGER.0.lock().unwrap()[0].contains("\"qa\""));
+    /// ```
+    pub fn parse_or_default(value: &str) -> Self {
+        Environment::from_str(value).unwrap_or_else(|e| {
+            let env = Environment::default();
+            warn!("{e}, using \"{env}\"");
+            env
+        })
+    }
+
+    /// Register custom names for the environments, e.g. to support the
+    /// vocabulary of an organization, that are accepted by the [`FromStr`]
+    /// implementation and so by [`Environment::init()`], case insensitive.
+    /// The built-in names and aliases can't be overridden.
+    ///
+    /// The aliases can be registered only once, and it must be done at start-up,
+    /// before the first environment is parsed, otherwise an error is raised
+    /// (or the aliases are missed by the environments parsed before).
+    /// The registration is thread-safe.
+    /// # Examples
+    /// ```
+    /// use std::env;
+    /// use server_env_config::env::Environment;
+    ///
+    /// Environment::register_aliases(&[("uat", Environment::Stage), ("live", Environment::Production)])
+    ///     .unwrap();
+    /// assert_eq!("UAT".parse::<Environment>().unwrap(), Environment::Stage);
+    /// env::set_var("APP_ENV", "live");
+    /// assert_eq!(Environment::init().unwrap(), Environment::Production);
+    ///
+    /// // Only once
+    /// assert!(Environment::register_aliases(&[("qa", Environment::Test)]).is_err());
+    /// assert!("qa".parse::<Environment>().is_err());
+    /// ```
+    pub fn register_aliases(aliases: &[(&str, Environment)]) -> Result<()> {
+        let aliases = aliases
+            .iter()
+            .map(|(name, env)| (name.trim().to_lowercase(), env.clone()))
+            .collect();
+        if ALIASES.set(aliases).is_err() {
+            bail!("environment aliases already registered");
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Environment {
+    type Err = Error;
+
+    /// Parse an environment name, case insensitive, also accepting
+    /// the common aliases "dev" and "development" for [`Environment::Local`],
+    /// "testing" for [`Environment::Test`], "staging" for [`Environment::Stage`]
+    /// and "prod" for [`Environment::Production`].
+    /// # Examples
+    /// ```
+    /// use server_env_config::env::Environment;
+    ///
+    /// assert_eq!("production".parse::<Environment>().unwrap(), Environment::Production);
+    /// assert_eq!("Production".parse::<Environment>().unwrap(), Environment::Production);
+    /// assert_eq!("PROD".parse::<Environment>().unwrap(), Environment::Production);
+    /// assert_eq!("Staging".parse::<Environment>().unwrap(), Environment::Stage);
+    /// assert_eq!("dev".parse::<Environment>().unwrap(), Environment::Local);
+    /// assert_eq!(" test ".parse::<Environment>().unwrap(), Environment::Test);
+    ///
+    /// let err = "qa".parse::<Environment>().unwrap_err();
+    /// assert_eq!(
+    ///     err.to_string(),
+    ///     "invalid environment \"qa\", valid values are: local, test, stage, production"
+    /// );
+    ///
+    /// // Close names get a suggestion
+    /// let err = "prodcution".parse::<Environment>().unwrap_err();
+    /// assert_eq!(
+    ///     err.to_string(),
+    ///     "invalid environment \"prodcution\", did you mean \"production\"? \
+    ///      valid values are: local, test, stage, production"
+    /// );
+    /// ```
+    fn from_str(s: &str) -> Result<Self> {
+        let name = s.trim().to_lowercase();
+        let custom = ALIASES.get();
+        let names = NAMES
+            .iter()
+            .map(|(n, e)| (*n, e))
+            .chain(custom.into_iter().flatten().map(|(n, e)| (n.as_str(), e)));
+        if let Some((_, env)) = names.clone().find(|(n, _)| *n == name) {
+            return Ok(env.clone());
+        }
+        // Suggest the closest name, if close enough to be a typo
+        let max_distance = if name.len() > 5 { 2 } else { 1 };
+        let suggestion = names
+            .map(|(n, _)| (n, edit_distance(&name, n)))
+            .filter(|(_, d)| *d <= max_distance)
+            .min_by_key(|(_, d)| *d)
+            .map(|(n, _)| format!(" did you mean \"{n}\"?"))
+            .unwrap_or_default();
+        Err(anyhow!(
+            "invalid environment \"{s}\",{suggestion} valid values are: {}",
+            Environment::VARIANTS.join(", ")
+        ))
+    }
+}
+
+/// Whether the app seems to be running inside a container, useful to choose
+/// defaults that make more sense there, e.g. binding to all the interfaces:
+///
+/// ```no_run
+/// use server_env_config::env::in_container;
+/// use server_env_config::server::HttpServerConfig;
+///
+/// // The HOST env variable still takes precedence
+/// let default_host = if in_container() { "0.0.0.0" } else { "127.0.0.1" };
+/// let server = HttpServerConfig::init_for(default_host, 8080).unwrap();
+/// ```
+///
+/// The heuristics used are: the `KUBERNETES_SERVICE_HOST` env variable set by
+/// Kubernetes, the `/.dockerenv` file created by Docker, the `/run/.containerenv`
+/// file created by Podman, or the cgroup of the process 1 naming a container
+/// runtime. It may fail with other runtimes, or with cgroup v2 where the cgroup
+/// path is usually just `/`, so explicit env variables should always be
+/// the authoritative source of settings.
+pub fn in_container() -> bool {
+    in_container_at(Path::new("/"))
+}
+
+/// Same as [`in_container()`], but looking for the files in the `root`
+/// directory passed instead of `/`, e.g. to test the detection.
+///
+/// # Examples
+/// ```
+/// use std::{env, fs};
+/// use server_env_config::env::in_container_at;
+///
+/// env::remove_var("KUBERNETES_SERVICE_HOST");
+/// let root = env::temp_dir().join("in_container_at");
+/// fs::create_dir_all(root.join("proc/1")).unwrap();
+/// fs::write(root.join("proc/1/cgroup"), "0::/\n").unwrap();
+/// assert!(!in_container_at(&root));
+///
+/// fs::write(root.join("proc/1/cgroup"), "12:pids:/kubepods/burstable/pod1234\n").unwrap();
+/// assert!(in_container_at(&root));
+///
+/// fs::write(root.join("proc/1/cgroup"), "0::/\n").unwrap();
+/// fs::write(root.join(".dockerenv"), "").unwrap();
+/// assert!(in_container_at(&root));
+///
+/// fs::remove_file(root.join(".dockerenv")).unwrap();
+/// env::set_var("KUBERNETES_SERVICE_HOST", "10.0.0.1");
+/// assert!(in_container_at(&root));
+/// ```
+pub fn in_container_at(root: &Path) -> bool {
+    var(KUBERNETES_SERVICE_HOST).is_ok()
+        || root.join(".dockerenv").exists()
+        || root.join("run/.containerenv").exists()
+        || fs::read_to_string(root.join("proc/1/cgroup")).is_ok_and(|cgroup| {
+            ["docker", "kubepods", "containerd", "libpod", "lxc"]
+                .iter()
+                .any(|runtime| cgroup.contains(runtime))
+        })
+}
+
+impl FromEnv for Environment {
+    /// Same as [`Environment::init()`].
+    fn from_env() -> Result<Self> {
+        Environment::init()
+    }
 }
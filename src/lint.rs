@@ -0,0 +1,110 @@
+//! The [`Warning`] struct reports non-fatal issues with the environment
+//! variables, e.g. values that look wrong or names that look misspelled.
+
+use crate::env::Environment;
+use crate::source;
+use crate::vars::{ALL, DATABASE_URL};
+use std::cell::RefCell;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use strum::VariantNames;
+
+thread_local! {
+    /// Warnings raised in the current thread while they are collected, see [`collected()`].
+    static COLLECTOR: RefCell<Option<Vec<Warning>>> = const { RefCell::new(None) };
+}
+
+/// A non-fatal issue with an environment variable: the configuration
+/// is still set up, but the value or the name looks wrong.
+///
+/// The warnings raised while initializing the config are always logged,
+/// and also returned by [`Config::init_with_warnings()`](crate::Config::init_with_warnings),
+/// e.g. a `DB_ACCEPT_INVALID_CERTS` enabled in production, or an
+/// `IDLE_TIMEOUT_SEC` that looks set in milliseconds. The variables that
+/// look misspelled are reported by [`Config::lint_env()`](crate::Config::lint_env).
+///
+/// It's printed as `{name}: {message}`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Warning {
+    /// Name of the variable the warning is about.
+    pub name: String,
+    /// Human description of the issue found, without the variable name.
+    pub message: String,
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.name, self.message)
+    }
+}
+
+/// Log a warning about the variable `name`, also collecting it
+/// if the warnings are collected in the current thread.
+pub(crate) fn warn(name: &str, message: String) {
+    log::warn!("⚠️  {name} {message}");
+    COLLECTOR.with(|c| {
+        if let Some(warnings) = c.borrow_mut().as_mut() {
+            warnings.push(Warning { name: name.to_string(), message });
+        }
+    });
+}
+
+/// Run the function passed collecting the warnings raised in the current thread.
+pub(crate) fn collected<T>(f: impl FnOnce() -> T) -> (T, Vec<Warning>) {
+    let previous = COLLECTOR.with(|c| c.replace(Some(Vec::new())));
+    let result = f();
+    let warnings = COLLECTOR.with(|c| c.replace(previous)).unwrap_or_default();
+    // Also collected by the outer call, if any
+    COLLECTOR.with(|c| {
+        if let Some(outer) = c.borrow_mut().as_mut() {
+            outer.extend(warnings.iter().cloned());
+        }
+    });
+    (result, warnings)
+}
+
+/// Look for environment variables set that are not read by the crate,
+/// but whose names are close to the name of a variable read by the crate,
+/// e.g. `MAX_CONECTIONS` instead of `MAX_CONNECTIONS`.
+pub(crate) fn lint_env() -> Vec<Warning> {
+    let mut known: Vec<String> = ALL.iter().map(|v| v.to_string()).collect();
+    known.extend(
+        Environment::VARIANTS
+            .iter()
+            .map(|e| format!("{DATABASE_URL}_{}", e.to_uppercase())),
+    );
+    let mut warnings: Vec<Warning> = source::vars()
+        .into_iter()
+        .map(|(name, _)| name)
+        .filter(|name| !known.contains(name))
+        .filter_map(|name| {
+            let max_distance = if name.len() > 5 { 2 } else { 1 };
+            known
+                .iter()
+                .map(|k| (k, edit_distance(&name, k)))
+                .filter(|(_, d)| *d <= max_distance)
+                .min_by_key(|(_, d)| *d)
+                .map(|(k, _)| Warning {
+                    message: format!("not a known variable, did you mean {k}?"),
+                    name,
+                })
+        })
+        .collect();
+    warnings.sort_by(|a, b| a.name.cmp(&b.name));
+    warnings
+}
+
+/// Levenshtein distance between the two strings passed.
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut curr = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        prev = curr;
+    }
+    prev[b.len()]
+}
@@ -0,0 +1,92 @@
+//! The [`LogFormat`] enum represents the possible formats of the application logs,
+//! and [`color_enabled()`] tells whether colored output is supported.
+
+use crate::audit::var;
+use crate::env::Environment;
+use crate::vars::{FORCE_COLOR, LOG_FORMAT, NO_COLOR};
+use anyhow::{anyhow, Result};
+use std::io::IsTerminal;
+use std::str::FromStr;
+use strum::VariantNames;
+use strum_macros::{Display, EnumString, EnumVariantNames};
+
+/// Possible formats of the application logs, to be used to
+/// configure the logging subscriber of the application.
+#[derive(Debug, Display, PartialEq, Eq, EnumString, EnumVariantNames, Clone, Copy)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum LogFormat {
+    /// Human-readable, multi-line logs.
+    Pretty,
+    /// Structured logs, one JSON object per line.
+    Json,
+    /// Human-readable, single-line logs.
+    Compact,
+}
+
+impl LogFormat {
+    /// Default format for the environment passed: [`LogFormat::Pretty`] in the
+    /// [`Environment::Local`] and [`Environment::Test`] environments, and
+    /// [`LogFormat::Json`] in the deployed ones.
+    pub fn default_for(env: &Environment) -> Self {
+        if *env >= Environment::Stage {
+            LogFormat::Json
+        } else {
+            LogFormat::Pretty
+        }
+    }
+
+    /// Get the value from the environment variable `LOG_FORMAT`, case insensitive,
+    /// otherwise defaulted to [`LogFormat::default_for()`] the environment passed.
+    /// It raises an error if the string doesn't match a possible format.
+    /// # Examples
+    /// ```
+    /// use std::env;
+    /// use server_env_config::env::Environment;
+    /// use server_env_config::logging::LogFormat;
+    ///
+    /// assert_eq!(LogFormat::init_for(&Environment::Local).unwrap(), LogFormat::Pretty);
+    /// assert_eq!(LogFormat::init_for(&Environment::Production).unwrap(), LogFormat::Json);
+    ///
+    /// env::set_var("LOG_FORMAT", "Compact");
+    /// assert_eq!(LogFormat::init_for(&Environment::Production).unwrap(), LogFormat::Compact);
+    ///
+    /// env::set_var("LOG_FORMAT", "xml");
+    /// let err = LogFormat::init_for(&Environment::Local).unwrap_err();
+    /// assert_eq!(err.to_string(), "LOG_FORMAT invalid value \"xml\", valid values are: pretty, json, compact");
+    /// ```
+    pub fn init_for(env: &Environment) -> Result<Self> {
+        match var(LOG_FORMAT) {
+            Err(_) => Ok(LogFormat::default_for(env)),
+            Ok(format) => LogFormat::from_str(format.trim()).map_err(|_| {
+                anyhow!(
+                    "LOG_FORMAT invalid value \"{format}\", valid values are: {}",
+                    LogFormat::VARIANTS.join(", ")
+                )
+            }),
+        }
+    }
+}
+
+/// Whether the output printed to the standard output can use ANSI colors,
+/// following the <https://no-color.org> conventions: colors are forced on if
+/// `FORCE_COLOR` is set (not empty and not "0"), disabled if `NO_COLOR` is set
+/// (not empty), otherwise enabled only if the standard output is a terminal,
+/// so the output captured to files or log collectors stays plain.
+/// # Examples
+/// ```
+/// use std::env;
+/// use server_env_config::logging::color_enabled;
+///
+/// env::set_var("NO_COLOR", "1");
+/// assert!(!color_enabled());
+/// env::set_var("FORCE_COLOR", "1");
+/// assert!(color_enabled());
+/// env::set_var("FORCE_COLOR", "0");
+/// assert!(!color_enabled());
+/// ```
+pub fn color_enabled() -> bool {
+    if var(FORCE_COLOR).is_ok_and(|v| !v.is_empty() && v != "0") {
+        return true;
+    }
+    !var(NO_COLOR).is_ok_and(|v| !v.is_empty()) && std::io::stdout().is_terminal()
+}
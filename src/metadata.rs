@@ -0,0 +1,175 @@
+//! The [`CloudMetadata`] struct represents values of the instance where the app
+//! runs, like the region, taken from the metadata service of the cloud provider.
+//!
+//! The types are always available, while fetching the values from the metadata
+//! service is only available with the `cloud-metadata` feature.
+
+#[cfg(feature = "cloud-metadata")]
+use crate::Config;
+#[cfg(feature = "cloud-metadata")]
+use std::io::{Read, Write};
+#[cfg(feature = "cloud-metadata")]
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+#[cfg(feature = "cloud-metadata")]
+use std::time::{Duration, Instant};
+
+/// Address of the instance metadata service, the same in AWS and GCP.
+#[cfg(feature = "cloud-metadata")]
+const METADATA_ADDR: &str = "169.254.169.254:80";
+#[cfg(feature = "cloud-metadata")]
+const AWS_TOKEN_TTL_SEC: u32 = 60;
+
+/// Cloud provider whose metadata service answered.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CloudProvider {
+    /// Amazon Web Services (EC2, ECS, EKS...).
+    Aws,
+    /// Google Cloud Platform (Compute Engine, GKE...).
+    Gcp,
+}
+
+/// Values of the instance where the app runs, fetched from the metadata
+/// service of the cloud provider with `CloudMetadata::fetch()`, only
+/// available with the `cloud-metadata` feature.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CloudMetadata {
+    /// Provider whose metadata service answered.
+    pub provider: CloudProvider,
+    /// Region where the instance runs, e.g. `us-east-1` or `us-central1`.
+    pub region: String,
+    /// Id of the instance.
+    pub instance_id: String,
+}
+
+#[cfg(feature = "cloud-metadata")]
+impl CloudMetadata {
+    /// Fetch the instance values from the metadata service at `169.254.169.254`,
+    /// trying first the AWS endpoints (IMDSv2):
+    ///
+    /// - `PUT /latest/api/token` to get a session token.
+    /// - `GET /latest/meta-data/placement/region`
+    /// - `GET /latest/meta-data/instance-id`
+    ///
+    /// and then the GCP endpoints, with the `Metadata-Flavor: Google` header:
+    ///
+    /// - `GET /computeMetadata/v1/instance/zone`, the region is taken from the zone.
+    /// - `GET /computeMetadata/v1/instance/id`
+    ///
+    /// All the requests together take at most `timeout`. `None` is returned
+    /// if the service is unavailable, e.g. when not running in the cloud, it
+    /// doesn't answer in time or the answers are unexpected, so the start-up
+    /// is never blocked or failed by the metadata service.
+    pub fn fetch(timeout: Duration) -> Option<Self> {
+        Self::fetch_from(METADATA_ADDR, timeout)
+    }
+
+    /// Same as [`CloudMetadata::fetch()`], but with the address of the metadata
+    /// service passed, e.g. to test with a mocked service.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::io::{Read, Write};
+    /// use std::net::TcpListener;
+    /// use std::thread;
+    /// use std::time::{Duration, Instant};
+    /// use server_env_config::metadata::{CloudMetadata, CloudProvider};
+    ///
+    /// // Mocked AWS metadata service
+    /// let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    /// let addr = listener.local_addr().unwrap().to_string();
+    /// thread::spawn(move || {
+    ///     for stream in listener.incoming() {
+    ///         let mut stream = stream.unwrap();
+    ///         let mut request = [0; 1024];
+    ///         let n = stream.read(&mut request).unwrap();
+    ///         let request = String::from_utf8_lossy(&request[..n]);
+    ///         let body = match request.split(' ').nth(1).unwrap() {
+    ///             "/latest/api/token" => "t0k3n",
+    ///             "/latest/meta-data/placement/region" => "eu-west-1",
+    ///             "/latest/meta-data/instance-id" => "i-0123456789",
+    ///             _ => "",
+    ///         };
+    ///         write!(stream, "HTTP/1.0 200 OK\r\nContent-Length: {}\r\n\r\n{body}", body.len()).unwrap();
+    ///     }
+    /// });
+    ///
+    /// let metadata = CloudMetadata::fetch_from(&addr, Duration::from_secs(2)).unwrap();
+    /// assert_eq!(metadata.provider, CloudProvider::Aws);
+    /// assert_eq!(metadata.region, "eu-west-1");
+    /// assert_eq!(metadata.instance_id, "i-0123456789");
+    ///
+    /// // Service unavailable
+    /// let addr = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().to_string();
+    /// let start = Instant::now();
+    /// assert!(CloudMetadata::fetch_from(&addr, Duration::from_millis(200)).is_none());
+    /// assert!(start.elapsed() < Duration::from_secs(2));
+    /// ```
+    pub fn fetch_from(addr: &str, timeout: Duration) -> Option<Self> {
+        let addr = addr.to_socket_addrs().ok()?.next()?;
+        let deadline = Instant::now() + timeout;
+        fetch_aws(&addr, deadline).or_else(|| fetch_gcp(&addr, deadline))
+    }
+}
+
+#[cfg(feature = "cloud-metadata")]
+impl Config {
+    /// Set [`Config::cloud`] with the values fetched from the metadata service
+    /// of the cloud provider, see [`CloudMetadata::fetch()`]. If the service
+    /// is unavailable it's left `None`, waiting at most `timeout`.
+    ///
+    /// Only available with the `cloud-metadata` feature.
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use server_env_config::Config;
+    ///
+    /// let config = Config::init(8080).unwrap().with_cloud_metadata(Duration::from_secs(1));
+    /// if let Some(cloud) = &config.cloud {
+    ///     println!("Running in {}", cloud.region);
+    /// }
+    /// ```
+    pub fn with_cloud_metadata(mut self, timeout: Duration) -> Self {
+        self.cloud = CloudMetadata::fetch(timeout);
+        self
+    }
+}
+
+#[cfg(feature = "cloud-metadata")]
+fn fetch_aws(addr: &SocketAddr, deadline: Instant) -> Option<CloudMetadata> {
+    let ttl_header = format!("X-aws-ec2-metadata-token-ttl-seconds: {AWS_TOKEN_TTL_SEC}");
+    let token = http_request(addr, "PUT", "/latest/api/token", &ttl_header, deadline)?;
+    let token_header = format!("X-aws-ec2-metadata-token: {token}");
+    let region = http_request(addr, "GET", "/latest/meta-data/placement/region", &token_header, deadline)?;
+    let instance_id = http_request(addr, "GET", "/latest/meta-data/instance-id", &token_header, deadline)?;
+    Some(CloudMetadata { provider: CloudProvider::Aws, region, instance_id })
+}
+
+#[cfg(feature = "cloud-metadata")]
+fn fetch_gcp(addr: &SocketAddr, deadline: Instant) -> Option<CloudMetadata> {
+    let header = "Metadata-Flavor: Google";
+    // The zone has the form "projects/{number}/zones/{region}-{letter}"
+    let zone = http_request(addr, "GET", "/computeMetadata/v1/instance/zone", header, deadline)?;
+    let region = zone.rsplit('/').next()?.rsplit_once('-')?.0.to_string();
+    let instance_id = http_request(addr, "GET", "/computeMetadata/v1/instance/id", header, deadline)?;
+    Some(CloudMetadata { provider: CloudProvider::Gcp, region, instance_id })
+}
+
+/// Send an HTTP/1.0 request with the `header` passed, returning the body
+/// trimmed if the response is successful and not empty, or `None` if
+/// it fails or the `deadline` is reached.
+#[cfg(feature = "cloud-metadata")]
+fn http_request(addr: &SocketAddr, method: &str, path: &str, header: &str, deadline: Instant) -> Option<String> {
+    let remaining = || deadline.checked_duration_since(Instant::now()).filter(|d| !d.is_zero());
+    let mut stream = TcpStream::connect_timeout(addr, remaining()?).ok()?;
+    stream.set_write_timeout(Some(remaining()?)).ok()?;
+    let request = format!("{method} {path} HTTP/1.0\r\nHost: {addr}\r\n{header}\r\nContent-Length: 0\r\n\r\n");
+    stream.write_all(request.as_bytes()).ok()?;
+    stream.set_read_timeout(Some(remaining()?)).ok()?;
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).ok()?;
+    let response = String::from_utf8(response).ok()?;
+    let (head, body) = response.split_once("\r\n\r\n")?;
+    let status = head.split(' ').nth(1)?;
+    let body = body.trim();
+    (status == "200" && !body.is_empty()).then(|| body.to_string())
+}
@@ -1,57 +1,1147 @@
 //! The [`HttpServerConfig`] struct represents configuration for an HTTP server.
 
-use crate::env_parsable;
+use crate::audit::var;
+use crate::conf::quoted_env_line;
+use crate::{env_bool, env_first, env_parsable, FromEnv};
+use crate::vars::{
+    APP_URI, HOST, HOST_ALIASES, HTTP_PROTOCOLS, HTTP_REDIRECT_PORT, HTTP_TLS, LISTEN_BACKLOG, PORT,
+    READ_TIMEOUT_SEC, TRAILING_SLASH, UNIX_SOCKET_PATH, WRITE_TIMEOUT_SEC,
+};
 
-use anyhow::Result;
-use std::env;
+use anyhow::{anyhow, bail, Context, Result};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::net::{Ipv6Addr, SocketAddr, ToSocketAddrs};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use std::time::Duration;
+use strum_macros::{Display, EnumString};
+
+/// Host address used if the `HOST` environment variable is not set.
+pub(crate) const DEFAULT_HOST: &str = "127.0.0.1";
+/// Port used by [`FromEnv`] if the `PORT` environment variable is not set.
+pub(crate) const DEFAULT_PORT: u16 = 8080;
+const DEFAULT_LISTEN_BACKLOG: u32 = 1024;
+/// Ports below this one are privileged on Unix systems.
+const FIRST_UNPRIVILEGED_PORT: u16 = 1024;
+
+/// A TCP port number, to avoid mixing up ports with other `u16` values,
+/// and to validate them in one place.
+///
+/// The port `0` is valid: when binding, it means an ephemeral port chosen
+/// by the OS, see [`Port::is_ephemeral()`]. The `port` of [`HttpServerConfig`]
+/// is kept as `u16` to not break the existing code, use
+/// [`HttpServerConfig::port_typed()`] to get it as `Port`.
+///
+/// # Examples
+/// ```
+/// use server_env_config::server::Port;
+///
+/// let port: Port = "8080".parse().unwrap();
+/// assert_eq!(port, Port(8080));
+/// assert_eq!(port.to_string(), "8080");
+/// assert_eq!(u16::from(port), 8080);
+/// assert!(!port.is_privileged());
+/// assert!(!port.is_ephemeral());
+/// assert_eq!(" 443 ".parse::<Port>().unwrap(), Port(443));
+/// assert!(Port(443).is_privileged());
+/// assert!("0".parse::<Port>().unwrap().is_ephemeral());
+///
+/// let err = "65536".parse::<Port>().unwrap_err();
+/// assert_eq!(err.to_string(), "invalid port \"65536\", expected a number from 0 to 65535");
+/// assert!("-1".parse::<Port>().is_err());
+/// assert!("http".parse::<Port>().is_err());
+/// assert!("".parse::<Port>().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Port(pub u16);
+
+impl Port {
+    /// Get the port number.
+    pub fn get(self) -> u16 {
+        self.0
+    }
+
+    /// Whether it's the port `0`, that when binding means
+    /// an ephemeral port chosen by the OS.
+    pub fn is_ephemeral(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Whether it's a privileged port (1-1023), that on Unix systems can
+    /// only be bound by root or with the `CAP_NET_BIND_SERVICE` capability.
+    pub fn is_privileged(self) -> bool {
+        !self.is_ephemeral() && self.0 < FIRST_UNPRIVILEGED_PORT
+    }
+}
+
+impl FromStr for Port {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        s.trim()
+            .parse::<u16>()
+            .map(Port)
+            .map_err(|_| anyhow!("invalid port \"{s}\", expected a number from 0 to 65535"))
+    }
+}
+
+impl Display for Port {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<u16> for Port {
+    fn from(port: u16) -> Self {
+        Port(port)
+    }
+}
+
+impl From<Port> for u16 {
+    fn from(port: Port) -> Self {
+        port.0
+    }
+}
+
+/// HTTP protocol versions the server should accept.
+#[derive(Debug, Default, Display, PartialEq, EnumString, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[strum(serialize_all = "snake_case")]
+pub enum HttpProtocols {
+    /// Only HTTP/1.x.
+    Http1,
+    /// Only HTTP/2 (h2 with TLS, or h2c in plain text).
+    Http2,
+    /// Both HTTP/1.x and HTTP/2, letting the server negotiate the protocol
+    /// (e.g. with ALPN when TLS is on).
+    #[default]
+    Auto,
+}
 
 /// Basic configuration for an HTTP server.
+///
+/// With the `serde` feature the fields are (de)serialized using as keys
+/// the environment variables that set them, like in the `.env` output.
+/// When deserialized, the field names are accepted as keys too (e.g. `port`),
+/// the missing keys take the same defaults than [`HttpServerConfig::new()`]
+/// with "127.0.0.1" and 8080, and the `APP_URL` is always calculated from
+/// the rest of the values:
+/// ```
+/// # #[cfg(feature = "serde")] {
+/// use std::time::Duration;
+/// use server_env_config::server::HttpServerConfig;
+///
+/// let server = HttpServerConfig::init_for("127.0.0.1", 8080).unwrap();
+/// let json = serde_json::to_value(&server).unwrap();
+/// let mut keys: Vec<&str> = json.as_object().unwrap().keys().map(|k| k.as_str()).collect();
+/// keys.sort();
+/// assert_eq!(
+///     keys,
+///     vec!["APP_URI", "APP_URL", "HOST", "HTTP_PROTOCOLS", "HTTP_TLS", "LISTEN_BACKLOG", "PORT", "TRAILING_SLASH"]
+/// );
+/// assert_eq!(json["PORT"], 8080);
+/// assert_eq!(json["HTTP_PROTOCOLS"], "auto");
+///
+/// let server: HttpServerConfig = serde_json::from_value(json).unwrap();
+/// assert_eq!(server.addr, "127.0.0.1");
+/// assert!(server.read_timeout.is_none());
+///
+/// // Timeouts in seconds
+/// let server = server.with_read_timeout(Some(Duration::from_secs(10)));
+/// let json = serde_json::to_value(&server).unwrap();
+/// assert_eq!(json["READ_TIMEOUT_SEC"], 10);
+/// let server: HttpServerConfig = serde_json::from_value(json).unwrap();
+/// assert_eq!(server.read_timeout, Some(Duration::from_secs(10)));
+///
+/// // Partial config, with defaults for the rest
+/// let server: HttpServerConfig = serde_json::from_str(r#"{"port": 9000, "APP_URI": "api"}"#).unwrap();
+/// assert_eq!(server.addr, "127.0.0.1");
+/// assert_eq!(server.backlog, 1024);
+/// assert!(server.trailing_slash);
+/// assert_eq!(server.url, "http://127.0.0.1:9000/api/");
+/// # }
+/// ```
 #[derive(Debug, Clone)]
+// The impls generated are wrapped below, to calculate the url when deserialized
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(remote = "Self", default))]
 pub struct HttpServerConfig {
     /// Host address, may be set by the `HOST` environment variable,
     /// requests will be limited to the address passed. Setting
     /// it to "0" means requests can be received from anywhere.
+    #[cfg_attr(feature = "serde", serde(rename = "HOST", alias = "addr"))]
     pub addr: String,
-    /// Host port, may be set by the `PORT` environment variable
+    /// Host port, may be set by the `PORT` environment variable. If a range
+    /// of ports is set, it's the first port of the range, see `port_range`.
+    #[cfg_attr(feature = "serde", serde(rename = "PORT", alias = "port"))]
     pub port: u16,
+    /// Range of ports to try binding in order, set by the `PORT` environment
+    /// variable with the `{first}-{last}` syntax, e.g. `PORT=8080-8090`, default
+    /// `None` when a single port is set. The first port available is bound by
+    /// [`HttpServerConfig::bind()`], while `port` and `url` keep the first
+    /// port of the range. Not (de)serialized with the `serde` feature.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub port_range: Option<RangeInclusive<u16>>,
     /// API URI (e.g. "/api"), may be set by the `APP_URI` environment variable
+    #[cfg_attr(feature = "serde", serde(rename = "APP_URI", alias = "uri"))]
     pub uri: String,
-    /// Final URL parsed: "http://{addr}:{port}/{uri}"
+    /// Final URL parsed: "http://{addr}:{port}/{uri}/", or with "https" if `tls` is on,
+    /// and without the final slash if `trailing_slash` is off
+    #[cfg_attr(feature = "serde", serde(rename = "APP_URL", skip_deserializing))]
     pub url: String,
+    /// HTTP protocol versions accepted, may be set by the `HTTP_PROTOCOLS`
+    /// environment variable with the values "http1", "http2" or "auto",
+    /// default "auto"
+    #[cfg_attr(feature = "serde", serde(rename = "HTTP_PROTOCOLS", alias = "protocols"))]
+    pub protocols: HttpProtocols,
+    /// Port of a second plain-text HTTP server used to redirect requests to
+    /// the main (TLS) port, may be set by the `HTTP_REDIRECT_PORT` environment
+    /// variable, default `None` (no redirect server)
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "HTTP_REDIRECT_PORT", alias = "redirect_port", skip_serializing_if = "Option::is_none")
+    )]
+    pub redirect_port: Option<u16>,
+    /// Whether the server is reached with TLS (HTTPS) or not, may be set by the
+    /// `HTTP_TLS` environment variable, default `false`. The TLS set-up itself
+    /// (certificates, etc.) is up to the server, the flag is used to build the `url`.
+    #[cfg_attr(feature = "serde", serde(rename = "HTTP_TLS", alias = "tls"))]
+    pub tls: bool,
+    /// Max length of the queue of pending TCP connections, for server builders
+    /// that accept a listen backlog, may be set by the `LISTEN_BACKLOG`
+    /// environment variable, default 1024. It must be greater than 0.
+    #[cfg_attr(feature = "serde", serde(rename = "LISTEN_BACKLOG", alias = "backlog"))]
+    pub backlog: u32,
+    /// Path of a Unix domain socket to listen on instead of a TCP port, may be
+    /// set by the `UNIX_SOCKET_PATH` environment variable, default `None`.
+    /// Setting it together with the `PORT` environment variable is an error,
+    /// as it's ambiguous where the server should listen.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "UNIX_SOCKET_PATH", alias = "socket_path", skip_serializing_if = "Option::is_none")
+    )]
+    pub socket_path: Option<String>,
+    /// Whether the `url` ends with a slash or not, may be set by the
+    /// `TRAILING_SLASH` environment variable, default `true`. The slash is
+    /// added after the `uri` if any, otherwise after the host and port,
+    /// e.g. "http://localhost:8080/api/" or "http://localhost:8080/",
+    /// and "http://localhost:8080/api" or "http://localhost:8080" if off.
+    #[cfg_attr(feature = "serde", serde(rename = "TRAILING_SLASH", alias = "trailing_slash"))]
+    pub trailing_slash: bool,
+    /// Max time to wait reading from a connection, e.g. the request headers
+    /// and body, for server builders that support it, may be set in seconds
+    /// by the `READ_TIMEOUT_SEC` environment variable, default `None` (no
+    /// timeout). Setting it to `0` means no timeout too. Useful to close
+    /// the connections of slow clients, e.g. in slowloris attacks.
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "READ_TIMEOUT_SEC",
+            alias = "read_timeout",
+            skip_serializing_if = "Option::is_none",
+            with = "option_secs"
+        )
+    )]
+    pub read_timeout: Option<Duration>,
+    /// Max time to wait writing to a connection, e.g. the response, for server
+    /// builders that support it, may be set in seconds by the `WRITE_TIMEOUT_SEC`
+    /// environment variable, default `None` (no timeout). Setting it to `0`
+    /// means no timeout too.
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "WRITE_TIMEOUT_SEC",
+            alias = "write_timeout",
+            skip_serializing_if = "Option::is_none",
+            with = "option_secs"
+        )
+    )]
+    pub write_timeout: Option<Duration>,
 }
 
 impl HttpServerConfig {
+    /// Create a configuration with the host address and port passed, and
+    /// default values for the rest of the settings, without reading
+    /// the environment.
+    ///
+    /// # Examples
+    /// ```
+    /// use server_env_config::server::HttpServerConfig;
+    ///
+    /// let server = HttpServerConfig::new("0.0.0.0", 3000).with_uri("api");
+    /// assert_eq!(server.url, "http://0.0.0.0:3000/api/");
+    /// assert!(server.redirect_port.is_none());
+    /// ```
+    pub fn new(addr: &str, port: u16) -> Self {
+        HttpServerConfig {
+            addr: addr.to_string(),
+            port,
+            port_range: None,
+            uri: "".to_string(),
+            url: build_url(addr, port, "", false, true),
+            protocols: HttpProtocols::default(),
+            redirect_port: None,
+            tls: false,
+            backlog: DEFAULT_LISTEN_BACKLOG,
+            socket_path: None,
+            trailing_slash: true,
+            read_timeout: None,
+            write_timeout: None,
+        }
+    }
+
     /// Initialize the configuration with the env variables `HOST`
     /// (otherwise default_host) and `PORT` (otherwise use default_port),
     /// and the env variable `APP_URI` is used to se the `uri`, otherwise
     /// defaulted to empty string.
+    ///
+    /// The `PORT` env variable may be a range of ports with the `{first}-{last}`
+    /// syntax, e.g. `PORT=8080-8090`, setting the `port_range` to try binding
+    /// each port in order until one is available, with `port` set to the first one.
+    ///
+    /// The env variable `HTTP_PROTOCOLS` sets the `protocols`, raising an
+    /// error if the value is not a valid option, and the env variable
+    /// `HTTP_REDIRECT_PORT` sets the optional `redirect_port`. The env variable
+    /// `HTTP_TLS` sets whether the `url` uses the "https" scheme or not, and
+    /// the env variable `LISTEN_BACKLOG` sets the `backlog`. The env variable
+    /// `UNIX_SOCKET_PATH` sets the `socket_path`, raising an error if `PORT` is
+    /// also set. The env variables `READ_TIMEOUT_SEC` and `WRITE_TIMEOUT_SEC`
+    /// set the optional `read_timeout` and `write_timeout`.
+    ///
+    /// If `HOST` is not set, the aliases `BIND_ADDRESS` and then `LISTEN_ADDRESS`
+    /// are read (see [`HttpServerConfig::init_with_host_aliases()`]), but `HOST`
+    /// is still the name used in the `to_string()` output.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::env;
+    /// use std::time::Duration;
+    /// use server_env_config::server::{HttpProtocols, HttpServerConfig};
+    ///
+    /// let server = HttpServerConfig::init_for("127.0.0.1", 8080).unwrap();
+    /// assert_eq!(server.protocols, HttpProtocols::Auto);
+    /// assert_eq!(server.backlog, 1024);
+    ///
+    /// env::set_var("LISTEN_BACKLOG", "4096");
+    /// let server = HttpServerConfig::init_for("127.0.0.1", 8080).unwrap();
+    /// assert_eq!(server.backlog, 4096);
+    /// assert!(server.to_string().contains("LISTEN_BACKLOG=4096"));
+    /// env::set_var("LISTEN_BACKLOG", "0");
+    /// assert!(HttpServerConfig::init_for("127.0.0.1", 8080).is_err());
+    /// env::remove_var("LISTEN_BACKLOG");
+    ///
+    /// // A Unix socket or a port, but not both
+    /// env::set_var("UNIX_SOCKET_PATH", "/run/app.sock");
+    /// let server = HttpServerConfig::init_for("127.0.0.1", 8080).unwrap();
+    /// assert_eq!(server.socket_path.as_deref(), Some("/run/app.sock"));
+    /// env::set_var("PORT", "9000");
+    /// let err = HttpServerConfig::init_for("127.0.0.1", 8080).unwrap_err();
+    /// assert!(err.to_string().contains("PORT and UNIX_SOCKET_PATH are both set"));
+    /// env::remove_var("UNIX_SOCKET_PATH");
+    /// let server = HttpServerConfig::init_for("127.0.0.1", 8080).unwrap();
+    /// assert_eq!(server.port, 9000);
+    /// assert!(server.socket_path.is_none());
+    /// env::remove_var("PORT");
+    ///
+    /// // Range of ports, the first available is bound
+    /// env::set_var("PORT", "9000-9010");
+    /// let server = HttpServerConfig::init_for("127.0.0.1", 8080).unwrap();
+    /// assert_eq!(server.port, 9000);
+    /// assert_eq!(server.port_range, Some(9000..=9010));
+    /// assert_eq!(server.url, "http://127.0.0.1:9000/");
+    /// assert!(server.to_string().contains("PORT=9000-9010\n"));
+    /// env::set_var("PORT", "9000-9000");
+    /// assert_eq!(HttpServerConfig::init_for("127.0.0.1", 8080).unwrap().port_range, Some(9000..=9000));
+    /// env::set_var("PORT", "9010-9000");
+    /// let err = HttpServerConfig::init_for("127.0.0.1", 8080).unwrap_err();
+    /// assert!(err.to_string().contains("PORT invalid range \"9010-9000\""));
+    /// env::set_var("PORT", "9000-");
+    /// assert!(HttpServerConfig::init_for("127.0.0.1", 8080).is_err());
+    /// env::set_var("PORT", "9000");
+    /// let server = HttpServerConfig::init_for("127.0.0.1", 8080).unwrap();
+    /// assert_eq!(server.port, 9000);
+    /// assert!(server.port_range.is_none());
+    /// env::remove_var("PORT");
+    ///
+    /// // Connection timeouts, none by default
+    /// assert!(server.read_timeout.is_none());
+    /// assert!(server.write_timeout.is_none());
+    /// assert!(!server.to_string().contains("TIMEOUT"));
+    /// env::set_var("READ_TIMEOUT_SEC", "10");
+    /// env::set_var("WRITE_TIMEOUT_SEC", "30");
+    /// let server = HttpServerConfig::init_for("127.0.0.1", 8080).unwrap();
+    /// assert_eq!(server.read_timeout, Some(Duration::from_secs(10)));
+    /// assert_eq!(server.write_timeout, Some(Duration::from_secs(30)));
+    /// assert!(server.to_string().ends_with("\nREAD_TIMEOUT_SEC=10\nWRITE_TIMEOUT_SEC=30"));
+    /// env::set_var("READ_TIMEOUT_SEC", "0");
+    /// let server = HttpServerConfig::init_for("127.0.0.1", 8080).unwrap();
+    /// assert!(server.read_timeout.is_none());
+    /// env::set_var("WRITE_TIMEOUT_SEC", "30s");
+    /// assert!(HttpServerConfig::init_for("127.0.0.1", 8080).is_err());
+    /// env::remove_var("READ_TIMEOUT_SEC");
+    /// env::remove_var("WRITE_TIMEOUT_SEC");
+    ///
+    /// env::set_var("HTTP_PROTOCOLS", "http2");
+    /// let server = HttpServerConfig::init_for("127.0.0.1", 8080).unwrap();
+    /// assert_eq!(server.protocols, HttpProtocols::Http2);
+    ///
+    /// env::set_var("HTTP_PROTOCOLS", "http3");
+    /// assert!(HttpServerConfig::init_for("127.0.0.1", 8080).is_err());
+    /// env::remove_var("HTTP_PROTOCOLS");
+    ///
+    /// // Slashes around the URI are ignored
+    /// env::set_var("APP_URI", "/api/");
+    /// let server = HttpServerConfig::init_for("127.0.0.1", 8080).unwrap();
+    /// assert_eq!(server.url, "http://127.0.0.1:8080/api/");
+    /// env::set_var("APP_URI", "/");
+    /// let server = HttpServerConfig::init_for("127.0.0.1", 8080).unwrap();
+    /// assert_eq!(server.url, "http://127.0.0.1:8080/");
+    /// env::set_var("APP_URI", "//");
+    /// let server = HttpServerConfig::init_for("127.0.0.1", 8080).unwrap();
+    /// assert_eq!(server.url, "http://127.0.0.1:8080/");
+    /// ```
     pub fn init_for(default_host: &str, default_port: u16) -> Result<HttpServerConfig> {
-        let addr = env::var("HOST").unwrap_or(default_host.to_string());
-        let port = env_parsable::<u16>("PORT", default_port)?;
-        let uri = env::var("APP_URI").unwrap_or("".to_string());
-        let url = format!("http://{}{}{}/",
-                          if addr == "0" { "localhost" } else { &addr },
-                          if port == 80 { "".to_string() } else { format!(":{}", port) },
-                          if uri.is_empty() { "".to_string() } else { format!("/{}", uri) });
-        Ok(HttpServerConfig { addr, port, uri, url })
+        Self::init_with_host_aliases(default_host, default_port, HOST_ALIASES)
+    }
+
+    /// Same as [`HttpServerConfig::init_for()`] with the crate defaults: "127.0.0.1"
+    /// as host address if `HOST` (or its aliases) is not set, and 8080 as port
+    /// if `PORT` is not set. It's also the [`FromEnv`] implementation, available
+    /// without importing the trait.
+    ///
+    /// # Examples
+    /// ```
+    /// use server_env_config::server::HttpServerConfig;
+    ///
+    /// let server = HttpServerConfig::from_env().unwrap();
+    /// assert_eq!(server.addr, "127.0.0.1");
+    /// assert_eq!(server.port, 8080);
+    /// assert_eq!(server.url, "http://127.0.0.1:8080/");
+    /// ```
+    pub fn from_env() -> Result<HttpServerConfig> {
+        Self::init_for(DEFAULT_HOST, DEFAULT_PORT)
+    }
+
+    /// Same as [`HttpServerConfig::init_for()`], but reading the host address from
+    /// the env variables passed as `host_aliases`, in order, if `HOST` is not set.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::env;
+    /// use server_env_config::server::HttpServerConfig;
+    ///
+    /// env::set_var("BIND_ADDRESS", "0.0.0.0");
+    /// let server = HttpServerConfig::init_for("127.0.0.1", 8080).unwrap();
+    /// assert_eq!(server.addr, "0.0.0.0");
+    /// assert!(server.to_string().contains("HOST=0.0.0.0\n"));
+    /// // HOST takes precedence
+    /// env::set_var("HOST", "localhost");
+    /// let server = HttpServerConfig::init_for("127.0.0.1", 8080).unwrap();
+    /// assert_eq!(server.addr, "localhost");
+    /// env::remove_var("HOST");
+    ///
+    /// // Custom aliases
+    /// env::set_var("SERVER_HOST", "::");
+    /// let server = HttpServerConfig::init_with_host_aliases("127.0.0.1", 8080, &["SERVER_HOST"]).unwrap();
+    /// assert_eq!(server.addr, "::");
+    /// let server = HttpServerConfig::init_with_host_aliases("127.0.0.1", 8080, &[]).unwrap();
+    /// assert_eq!(server.addr, "127.0.0.1");
+    /// ```
+    pub fn init_with_host_aliases(
+        default_host: &str,
+        default_port: u16,
+        host_aliases: &[&str],
+    ) -> Result<HttpServerConfig> {
+        let addr = var(HOST)
+            .ok()
+            .or_else(|| env_first(host_aliases))
+            .unwrap_or(default_host.to_string());
+        let port_range = env_port_range()?;
+        let port = match &port_range {
+            Some(range) => *range.start(),
+            None => env_parsable::<Port>(PORT, Port(default_port))?.get(),
+        };
+        let uri = var(APP_URI).unwrap_or("".to_string());
+        let tls = env_bool(HTTP_TLS, false)?;
+        let trailing_slash = env_bool(TRAILING_SLASH, true)?;
+        let url = build_url(&addr, port, &uri, tls, trailing_slash);
+        let protocols = match var(HTTP_PROTOCOLS) {
+            Err(_) => HttpProtocols::default(),
+            Ok(p) => HttpProtocols::from_str(p.to_lowercase().as_str())
+                .with_context(|| format!("HTTP_PROTOCOLS invalid value \"{p}\""))?,
+        };
+        let redirect_port = match var(HTTP_REDIRECT_PORT) {
+            Err(_) => None,
+            Ok(_) => Some(env_parsable::<u16>(HTTP_REDIRECT_PORT, 0)?),
+        };
+        let backlog = env_parsable::<u32>(LISTEN_BACKLOG, DEFAULT_LISTEN_BACKLOG)?;
+        if backlog == 0 {
+            bail!("LISTEN_BACKLOG must be greater than 0");
+        }
+        let socket_path = var(UNIX_SOCKET_PATH).ok();
+        if socket_path.is_some() && var(PORT).is_ok() {
+            bail!("PORT and UNIX_SOCKET_PATH are both set, it's ambiguous where to listen, \
+                   unset one of them");
+        }
+        let read_timeout = env_timeout_sec(READ_TIMEOUT_SEC)?;
+        let write_timeout = env_timeout_sec(WRITE_TIMEOUT_SEC)?;
+        Ok(HttpServerConfig {
+            addr,
+            port,
+            port_range,
+            uri,
+            url,
+            protocols,
+            redirect_port,
+            tls,
+            backlog,
+            socket_path,
+            trailing_slash,
+            read_timeout,
+            write_timeout,
+        })
+    }
+
+    /// Get the `url` without the trailing slash, e.g. for OpenAPI server lists.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::env;
+    /// use server_env_config::server::HttpServerConfig;
+    ///
+    /// let server = HttpServerConfig::init_for("127.0.0.1", 8080).unwrap();
+    /// assert_eq!(server.url, "http://127.0.0.1:8080/");
+    /// assert_eq!(server.url_no_trailing_slash(), "http://127.0.0.1:8080");
+    ///
+    /// env::set_var("APP_URI", "api/v1");
+    /// let server = HttpServerConfig::init_for("127.0.0.1", 8080).unwrap();
+    /// assert_eq!(server.url, "http://127.0.0.1:8080/api/v1/");
+    /// assert_eq!(server.url_no_trailing_slash(), "http://127.0.0.1:8080/api/v1");
+    /// ```
+    pub fn url_no_trailing_slash(&self) -> &str {
+        self.url.strip_suffix('/').unwrap_or(&self.url)
+    }
+
+    /// Get the `port` as a string.
+    pub fn port_str(&self) -> String {
+        self.port.to_string()
+    }
+
+    /// Get the `port` as a [`Port`].
+    ///
+    /// # Examples
+    /// ```
+    /// use server_env_config::server::{HttpServerConfig, Port};
+    ///
+    /// let server = HttpServerConfig::init_for("127.0.0.1", 8080).unwrap();
+    /// assert_eq!(server.port_typed(), Port(8080));
+    /// assert!(server.with_port(80).port_typed().is_privileged());
+    /// ```
+    pub fn port_typed(&self) -> Port {
+        Port(self.port)
+    }
+
+    /// Get the "host:port" authority used to reach the server, with IPv6
+    /// addresses enclosed in brackets, and "localhost" as host if `addr` is
+    /// the "0" wildcard, as in the `url`.
+    ///
+    /// # Examples
+    /// ```
+    /// use server_env_config::server::HttpServerConfig;
+    ///
+    /// let server = HttpServerConfig::init_for("127.0.0.1", 8080).unwrap();
+    /// assert_eq!(server.port_str(), "8080");
+    /// assert_eq!(server.authority(), "127.0.0.1:8080");
+    ///
+    /// let server = server.with_addr("::1");
+    /// assert_eq!(server.authority(), "[::1]:8080");
+    /// assert_eq!(server.url, "http://[::1]:8080/");
+    ///
+    /// let server = server.with_addr("0");
+    /// assert_eq!(server.authority(), "localhost:8080");
+    /// ```
+    pub fn authority(&self) -> String {
+        format!("{}:{}", url_host(&self.addr), self.port)
+    }
+
+    /// Set the host address, recomputing the `url`.
+    ///
+    /// The `with_*` setters don't read or change the environment, they are meant
+    /// to tweak a configuration already initialized.
+    ///
+    /// # Examples
+    /// ```
+    /// use server_env_config::server::HttpServerConfig;
+    ///
+    /// let server = HttpServerConfig::init_for("127.0.0.1", 8080).unwrap()
+    ///     .with_addr("0")
+    ///     .with_port(9000)
+    ///     .with_uri("api");
+    /// assert_eq!(server.port, 9000);
+    /// assert_eq!(server.url, "http://localhost:9000/api/");
+    /// ```
+    pub fn with_addr(mut self, addr: &str) -> Self {
+        self.addr = addr.to_string();
+        self.url = build_url(&self.addr, self.port, &self.uri, self.tls, self.trailing_slash);
+        self
+    }
+
+    /// Set the host port, recomputing the `url`, and removing the `port_range` if any.
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.port = port;
+        self.port_range = None;
+        self.url = build_url(&self.addr, self.port, &self.uri, self.tls, self.trailing_slash);
+        self
+    }
+
+    /// Set the range of ports to try binding in order, setting `port` with the
+    /// first port of the range, and recomputing the `url`.
+    pub fn with_port_range(mut self, port_range: RangeInclusive<u16>) -> Self {
+        self.port = *port_range.start();
+        self.port_range = Some(port_range);
+        self.url = build_url(&self.addr, self.port, &self.uri, self.tls, self.trailing_slash);
+        self
+    }
+
+    /// Get the `port` as a string, or the `port_range` with the
+    /// `{first}-{last}` syntax if set, as in the `PORT` env variable.
+    fn port_env_value(&self) -> String {
+        match &self.port_range {
+            Some(range) => format!("{}-{}", range.start(), range.end()),
+            None => self.port.to_string(),
+        }
+    }
+
+    /// Set the API URI, recomputing the `url`.
+    pub fn with_uri(mut self, uri: &str) -> Self {
+        self.uri = uri.to_string();
+        self.url = build_url(&self.addr, self.port, &self.uri, self.tls, self.trailing_slash);
+        self
+    }
+
+    /// Set the HTTP protocol versions accepted.
+    pub fn with_protocols(mut self, protocols: HttpProtocols) -> Self {
+        self.protocols = protocols;
+        self
+    }
+
+    /// Set the port of the redirect server, `None` to disable it.
+    pub fn with_redirect_port(mut self, redirect_port: Option<u16>) -> Self {
+        self.redirect_port = redirect_port;
+        self
+    }
+
+    /// Set the path of the Unix socket to listen on, `None` to listen on the TCP port.
+    pub fn with_socket_path(mut self, socket_path: Option<&str>) -> Self {
+        self.socket_path = socket_path.map(|p| p.to_string());
+        self
+    }
+
+    /// Set the max length of the queue of pending connections.
+    pub fn with_backlog(mut self, backlog: u32) -> Self {
+        self.backlog = backlog;
+        self
+    }
+
+    /// Set the max time to wait reading from a connection, `None` for no timeout.
+    pub fn with_read_timeout(mut self, read_timeout: Option<Duration>) -> Self {
+        self.read_timeout = read_timeout;
+        self
+    }
+
+    /// Set the max time to wait writing to a connection, `None` for no timeout.
+    pub fn with_write_timeout(mut self, write_timeout: Option<Duration>) -> Self {
+        self.write_timeout = write_timeout;
+        self
+    }
+
+    /// Set whether the server is reached with TLS or not, recomputing the `url`.
+    pub fn with_tls(mut self, tls: bool) -> Self {
+        self.tls = tls;
+        self.url = build_url(&self.addr, self.port, &self.uri, self.tls, self.trailing_slash);
+        self
+    }
+
+    /// Set whether the `url` ends with a slash or not, recomputing the `url`.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::env;
+    /// use server_env_config::server::HttpServerConfig;
+    ///
+    /// env::set_var("TRAILING_SLASH", "false");
+    /// let server = HttpServerConfig::init_for("127.0.0.1", 8080).unwrap();
+    /// assert_eq!(server.url, "http://127.0.0.1:8080");
+    /// assert!(server.to_string().contains("TRAILING_SLASH=false"));
+    /// let server = server.with_uri("api");
+    /// assert_eq!(server.url, "http://127.0.0.1:8080/api");
+    ///
+    /// let server = server.with_trailing_slash(true);
+    /// assert_eq!(server.url, "http://127.0.0.1:8080/api/");
+    /// assert_eq!(server.with_uri("").url, "http://127.0.0.1:8080/");
+    /// ```
+    pub fn with_trailing_slash(mut self, trailing_slash: bool) -> Self {
+        self.trailing_slash = trailing_slash;
+        self.url = build_url(&self.addr, self.port, &self.uri, self.tls, self.trailing_slash);
+        self
+    }
+
+    /// Get the URL scheme of the server: "https" if `tls` is on,
+    /// otherwise "http".
+    ///
+    /// # Examples
+    /// ```
+    /// use std::env;
+    /// use server_env_config::server::HttpServerConfig;
+    ///
+    /// let server = HttpServerConfig::init_for("127.0.0.1", 8080).unwrap();
+    /// assert_eq!(server.scheme(), "http");
+    ///
+    /// env::set_var("HTTP_TLS", "true");
+    /// let server = HttpServerConfig::init_for("127.0.0.1", 443).unwrap();
+    /// assert_eq!(server.scheme(), "https");
+    /// assert_eq!(server.url, "https://127.0.0.1/");
+    /// assert_eq!(server.with_tls(false).url, "http://127.0.0.1:443/");
+    /// ```
+    pub fn scheme(&self) -> &'static str {
+        if self.tls { "https" } else { "http" }
+    }
+
+    /// Get all the config values in a map, using as key the environment
+    /// variable used to set-up each value, even if the value was set
+    /// in another way, e.g. using a default value.
+    ///
+    /// `PORT` is left out when listening on a Unix socket (`socket_path`),
+    /// as it cannot be set together with `UNIX_SOCKET_PATH`.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::env;
+    /// use server_env_config::server::HttpServerConfig;
+    ///
+    /// env::set_var("UNIX_SOCKET_PATH", "/run/app.sock");
+    /// let server = HttpServerConfig::init_for("127.0.0.1", 8080).unwrap();
+    /// let map = server.to_env_map();
+    /// assert!(!map.contains_key("PORT"));
+    /// assert!(!server.to_string().contains("PORT="));
+    ///
+    /// // The values can be read back
+    /// for (name, value) in &map {
+    ///     env::set_var(name, value);
+    /// }
+    /// let server = HttpServerConfig::init_for("127.0.0.1", 8080).unwrap();
+    /// assert_eq!(server.socket_path.as_deref(), Some("/run/app.sock"));
+    /// assert_eq!(server.to_env_map(), map);
+    /// ```
+    pub fn to_env_map(&self) -> BTreeMap<String, String> {
+        let mut map = BTreeMap::from([
+            (APP_URI.to_string(), self.uri.clone()),
+            (HOST.to_string(), self.addr.clone()),
+            (HTTP_PROTOCOLS.to_string(), self.protocols.to_string()),
+            (HTTP_TLS.to_string(), self.tls.to_string()),
+            (LISTEN_BACKLOG.to_string(), self.backlog.to_string()),
+            (TRAILING_SLASH.to_string(), self.trailing_slash.to_string()),
+        ]);
+        if let Some(redirect_port) = self.redirect_port {
+            map.insert(HTTP_REDIRECT_PORT.to_string(), redirect_port.to_string());
+        }
+        match &self.socket_path {
+            Some(socket_path) => map.insert(UNIX_SOCKET_PATH.to_string(), socket_path.clone()),
+            None => map.insert(PORT.to_string(), self.port_env_value()),
+        };
+        if let Some(read_timeout) = self.read_timeout {
+            map.insert(READ_TIMEOUT_SEC.to_string(), read_timeout.as_secs().to_string());
+        }
+        if let Some(write_timeout) = self.write_timeout {
+            map.insert(WRITE_TIMEOUT_SEC.to_string(), write_timeout.as_secs().to_string());
+        }
+        map
+    }
+
+    /// Get the default values of the settings, in the same format
+    /// than [`HttpServerConfig::to_env_map()`], for the default
+    /// host and port passed.
+    pub(crate) fn default_env_map(default_host: &str, default_port: u16) -> BTreeMap<String, String> {
+        BTreeMap::from([
+            (APP_URI.to_string(), "".to_string()),
+            (HOST.to_string(), default_host.to_string()),
+            (PORT.to_string(), default_port.to_string()),
+            (HTTP_PROTOCOLS.to_string(), HttpProtocols::default().to_string()),
+            (HTTP_TLS.to_string(), false.to_string()),
+            (LISTEN_BACKLOG.to_string(), DEFAULT_LISTEN_BACKLOG.to_string()),
+            (TRAILING_SLASH.to_string(), true.to_string()),
+        ])
+    }
+
+    /// Resolve the socket address of the server from `addr` and `port`.
+    /// The `"0"` wildcard address resolves to `0.0.0.0`. With a `port_range`,
+    /// it's the address with the first port of the range, that may not be
+    /// available, see [`HttpServerConfig::socket_addrs()`].
+    ///
+    /// # Examples
+    /// ```
+    /// use std::env;
+    /// use server_env_config::server::HttpServerConfig;
+    ///
+    /// env::set_var("HTTP_REDIRECT_PORT", "80");
+    /// let server = HttpServerConfig::init_for("127.0.0.1", 443).unwrap();
+    /// assert_eq!(server.socket_addr().unwrap().to_string(), "127.0.0.1:443");
+    /// assert_eq!(server.redirect_socket_addr().unwrap().unwrap().to_string(), "127.0.0.1:80");
+    /// ```
+    pub fn socket_addr(&self) -> Result<SocketAddr> {
+        self.resolve(self.port)
+    }
+
+    /// Resolve the socket addresses of the server from `addr` and each port
+    /// of the `port_range` in order, or only `port` if no range is set,
+    /// e.g. to try binding each of them until one is available.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::env;
+    /// use server_env_config::server::HttpServerConfig;
+    ///
+    /// let server = HttpServerConfig::init_for("127.0.0.1", 8080).unwrap();
+    /// let addrs: Vec<String> = server.socket_addrs().unwrap().iter().map(|a| a.to_string()).collect();
+    /// assert_eq!(addrs, vec!["127.0.0.1:8080"]);
+    ///
+    /// env::set_var("PORT", "9000-9002");
+    /// let server = HttpServerConfig::init_for("127.0.0.1", 8080).unwrap();
+    /// let addrs: Vec<String> = server.socket_addrs().unwrap().iter().map(|a| a.to_string()).collect();
+    /// assert_eq!(addrs, vec!["127.0.0.1:9000", "127.0.0.1:9001", "127.0.0.1:9002"]);
+    /// assert_eq!(server.socket_addr().unwrap().to_string(), "127.0.0.1:9000");
+    /// ```
+    pub fn socket_addrs(&self) -> Result<Vec<SocketAddr>> {
+        match &self.port_range {
+            Some(range) => range.clone().map(|port| self.resolve(port)).collect(),
+            None => Ok(vec![self.resolve(self.port)?]),
+        }
+    }
+
+    /// Resolve the socket address of the redirect server from `addr` and
+    /// `redirect_port`, or `None` if no redirect port is set.
+    /// See [`HttpServerConfig::socket_addr()`].
+    pub fn redirect_socket_addr(&self) -> Result<Option<SocketAddr>> {
+        self.redirect_port.map(|port| self.resolve(port)).transpose()
+    }
+
+    fn resolve(&self, port: u16) -> Result<SocketAddr> {
+        let host = self.resolve_host();
+        (host, port)
+            .to_socket_addrs()
+            .with_context(|| format!("Invalid socket address {host}:{port}"))?
+            .next()
+            .with_context(|| format!("No socket address resolved for {host}:{port}"))
+    }
+
+    /// Host of the socket addresses, `0.0.0.0` if `addr` is the "0" wildcard.
+    fn resolve_host(&self) -> &str {
+        if self.addr == "0" { "0.0.0.0" } else { &self.addr }
+    }
+}
+
+/// Build the final URL: "http://{addr}:{port}/{uri}/", omitting the port
+/// if it's 80, and using "localhost" as host if `addr` is the "0" wildcard
+/// (IPv6 addresses are enclosed in brackets).
+/// Leading and trailing slashes in `uri` are ignored, so an `uri` with
+/// only slashes is the same as an empty one. The final slash is omitted
+/// if `trailing_slash` is false.
+fn build_url(addr: &str, port: u16, uri: &str, tls: bool, trailing_slash: bool) -> String {
+    let uri = uri.trim_matches('/');
+    let (scheme, default_port) = if tls { ("https", 443) } else { ("http", 80) };
+    format!("{}://{}{}{}{}",
+            scheme,
+            url_host(addr),
+            if port == default_port { "".to_string() } else { format!(":{}", port) },
+            if uri.is_empty() { "".to_string() } else { format!("/{}", uri) },
+            if trailing_slash { "/" } else { "" })
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for HttpServerConfig {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        HttpServerConfig::serialize(self, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for HttpServerConfig {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut server = HttpServerConfig::deserialize(deserializer)?;
+        server.url = build_url(&server.addr, server.port, &server.uri, server.tls, server.trailing_slash);
+        Ok(server)
+    }
+}
+
+/// (De)serialize an optional duration as a number of seconds, as set in the env variables.
+#[cfg(feature = "serde")]
+mod option_secs {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(value: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(duration) => serializer.serialize_some(&duration.as_secs()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Duration>, D::Error> {
+        Ok(Option::<u64>::deserialize(deserializer)?.map(Duration::from_secs))
     }
 }
 
-impl ToString for HttpServerConfig {
+/// Read the range of ports from the `PORT` env variable if it has the
+/// `{first}-{last}` syntax, `None` if it's not set or it's a single port.
+fn env_port_range() -> Result<Option<RangeInclusive<u16>>> {
+    let Ok(port) = var(PORT) else {
+        return Ok(None);
+    };
+    let Some((first, last)) = port.split_once('-') else {
+        return Ok(None);
+    };
+    let invalid = || format!("PORT invalid range \"{port}\"");
+    let first = first.parse::<Port>().with_context(invalid)?.get();
+    let last = last.parse::<Port>().with_context(invalid)?.get();
+    if first > last {
+        bail!("{}, the first port is greater than the last one", invalid());
+    }
+    Ok(Some(first..=last))
+}
+
+/// Read an optional timeout in seconds from the env variable passed,
+/// `None` if it's not set or it's `0`.
+fn env_timeout_sec(env_name: &'static str) -> Result<Option<Duration>> {
+    match var(env_name) {
+        Err(_) => Ok(None),
+        Ok(_) => Ok(Some(Duration::from_secs(env_parsable::<u64>(env_name, 0)?)).filter(|t| !t.is_zero())),
+    }
+}
+
+/// Host used to reach the server: "localhost" if `addr` is the "0"
+/// wildcard, and IPv6 addresses enclosed in brackets.
+fn url_host(addr: &str) -> String {
+    if addr == "0" {
+        "localhost".to_string()
+    } else if addr.parse::<Ipv6Addr>().is_ok() {
+        format!("[{addr}]")
+    } else {
+        addr.to_string()
+    }
+}
+
+/// Where the server should listen for connections.
+///
+/// Only available with the `systemd` feature on Unix systems.
+#[cfg(all(feature = "systemd", unix))]
+#[derive(Debug, Clone, PartialEq)]
+pub enum BindTarget {
+    /// Bind to the host address and port configured.
+    Tcp(String, u16),
+    /// Listen on a file descriptor already bound and passed
+    /// by systemd with socket activation.
+    Fd(std::os::unix::io::RawFd),
+}
+
+/// First file descriptor passed by systemd with socket activation
+/// (`SD_LISTEN_FDS_START`).
+#[cfg(all(feature = "systemd", unix))]
+const LISTEN_FDS_START: std::os::unix::io::RawFd = 3;
+
+/// Number of file descriptors passed by systemd with socket activation,
+/// or `None` if the process was not started with socket activation.
+///
+/// It reads the `LISTEN_FDS` environment variable, only if `LISTEN_PID`
+/// matches the current process ID, as systemd does.
+///
+/// Only available with the `systemd` feature on Unix systems.
+///
+/// # Examples
+/// ```
+/// use std::env;
+/// use std::process;
+/// use server_env_config::server::listen_fds;
+///
+/// assert_eq!(listen_fds(), None);
+///
+/// env::set_var("LISTEN_PID", process::id().to_string());
+/// env::set_var("LISTEN_FDS", "2");
+/// assert_eq!(listen_fds(), Some(2));
+///
+/// env::set_var("LISTEN_PID", "1");  // sockets for another process
+/// assert_eq!(listen_fds(), None);
+/// ```
+#[cfg(all(feature = "systemd", unix))]
+pub fn listen_fds() -> Option<u32> {
+    let pid = var(crate::vars::LISTEN_PID).ok()?.parse::<u32>().ok()?;
+    if pid != std::process::id() {
+        return None;
+    }
+    var(crate::vars::LISTEN_FDS)
+        .ok()?
+        .parse::<u32>()
+        .ok()
+        .filter(|fds| *fds > 0)
+}
+
+#[cfg(all(feature = "systemd", unix))]
+impl HttpServerConfig {
+    /// Get where the server should listen for connections: if the process was
+    /// started by systemd with socket activation (see [`listen_fds()`]), the
+    /// first file descriptor passed, ignoring `addr` and `port`, otherwise
+    /// the host address and port configured.
+    ///
+    /// Only available with the `systemd` feature on Unix systems.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::env;
+    /// use std::process;
+    /// use server_env_config::server::{BindTarget, HttpServerConfig};
+    ///
+    /// let server = HttpServerConfig::init_for("127.0.0.1", 8080).unwrap();
+    /// assert_eq!(server.bind_target(), BindTarget::Tcp("127.0.0.1".to_string(), 8080));
+    ///
+    /// env::set_var("LISTEN_PID", process::id().to_string());
+    /// env::set_var("LISTEN_FDS", "1");
+    /// assert_eq!(server.bind_target(), BindTarget::Fd(3));
+    /// ```
+    pub fn bind_target(&self) -> BindTarget {
+        match listen_fds() {
+            Some(_) => BindTarget::Fd(LISTEN_FDS_START),
+            None => BindTarget::Tcp(self.addr.clone(), self.port),
+        }
+    }
+}
+
+#[cfg(feature = "listener")]
+impl HttpServerConfig {
+    /// Bind a [`std::net::TcpListener`] to the `addr` and `port` configured.
+    ///
+    /// The `"0"` wildcard address binds to all the interfaces, trying first
+    /// the IPv6 wildcard `[::]` (dual-stack on most systems), and falling
+    /// back to the IPv4 wildcard `0.0.0.0`. If binding fails, the error
+    /// includes the address attempted, or both addresses with their errors
+    /// for the `"0"` wildcard.
+    ///
+    /// With a `port_range`, each port is tried in order, returning the listener
+    /// bound to the first port available, whose number can be got with
+    /// [`TcpListener::local_addr()`](std::net::TcpListener::local_addr).
+    ///
+    /// An error is returned if a `socket_path` is set, as the server must
+    /// listen on the Unix socket instead of a TCP port.
+    ///
+    /// Only available with the `listener` feature.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::net::TcpListener;
+    /// use server_env_config::server::HttpServerConfig;
+    ///
+    /// let server = HttpServerConfig::init_for("127.0.0.1", 0).unwrap();
+    /// let listener = server.bind().unwrap();
+    /// assert!(listener.local_addr().unwrap().ip().is_loopback());
+    ///
+    /// // The first port of the range is taken, so the next one is bound
+    /// let taken = TcpListener::bind("127.0.0.1:0").unwrap();
+    /// let port = taken.local_addr().unwrap().port();
+    /// if let Some(last) = port.checked_add(50) {
+    ///     let server = server.with_port_range(port..=last);
+    ///     let listener = server.bind().unwrap();
+    ///     assert!(listener.local_addr().unwrap().port() > port);
+    ///
+    ///     let server = server.with_port_range(port..=port);
+    ///     let err = server.bind().unwrap_err();
+    ///     assert!(err.to_string().contains(&format!("Failed to bind to 127.0.0.1:{port}-{port}")));
+    /// }
+    ///
+    /// // The wildcard port is taken, both addresses fail
+    /// let taken = TcpListener::bind("0.0.0.0:0").unwrap();
+    /// let port = taken.local_addr().unwrap().port();
+    /// let err = HttpServerConfig::new("0", port).bind().unwrap_err().to_string();
+    /// assert!(err.starts_with(&format!("Failed to bind to [::]:{port} (")));
+    /// assert!(err.contains(&format!(") and to 0.0.0.0:{port} (")));
+    ///
+    /// let server = HttpServerConfig::new("127.0.0.1", 0).with_socket_path(Some("/run/app.sock"));
+    /// assert!(server.bind().unwrap_err().to_string().contains("UNIX_SOCKET_PATH is set"));
+    /// ```
+    pub fn bind(&self) -> Result<std::net::TcpListener> {
+        if let Some(socket_path) = &self.socket_path {
+            bail!("UNIX_SOCKET_PATH is set, listen on the Unix socket \"{socket_path}\" instead of a TCP port");
+        }
+        match &self.port_range {
+            Some(range) => range.clone().find_map(|port| self.bind_port(port).ok()).with_context(|| {
+                format!("Failed to bind to {}:{}, no port available", self.resolve_host(), self.port_env_value())
+            }),
+            None => self.bind_port(self.port),
+        }
+    }
+
+    fn bind_port(&self, port: u16) -> Result<std::net::TcpListener> {
+        use std::net::TcpListener;
+        if self.addr == "0" {
+            return TcpListener::bind(("::", port)).or_else(|ipv6_err| {
+                TcpListener::bind(("0.0.0.0", port)).map_err(|ipv4_err| {
+                    anyhow!("Failed to bind to [::]:{port} ({ipv6_err}) and to 0.0.0.0:{port} ({ipv4_err})")
+                })
+            });
+        }
+        TcpListener::bind((self.addr.as_str(), port))
+            .with_context(|| format!("Failed to bind to {}:{port}", self.addr))
+    }
+}
+
+impl FromEnv for HttpServerConfig {
+    /// Same as [`HttpServerConfig::from_env()`].
+    fn from_env() -> Result<Self> {
+        HttpServerConfig::from_env()
+    }
+}
+
+impl Default for HttpServerConfig {
+    /// Same as [`HttpServerConfig::new()`] with "127.0.0.1" and 8080.
+    fn default() -> Self {
+        HttpServerConfig::new(DEFAULT_HOST, DEFAULT_PORT)
+    }
+}
+
+impl Display for HttpServerConfig {
     /// This `to_string()` implementation prints out all the config
     /// values in `.env` format, using as key the environment variable
     /// used to set-up the config, even if the configuration was
-    /// set in another way, e.g. using a default value.
-    fn to_string(&self) -> String {
-        format!(
-r#"# APP_URL --> {}
-APP_URI="{}"
-HOST={}
-PORT={}"#,
-            self.url,
-            self.uri,
-            self.addr,
-            self.port,
-        )
+    /// set in another way, e.g. using a default value. `PORT` is left
+    /// out when listening on a Unix socket, like in [`HttpServerConfig::to_env_map()`].
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "# APP_URL --> {}\n{}\nHOST={}", self.url, quoted_env_line(APP_URI, &self.uri), self.addr)?;
+        if self.socket_path.is_none() {
+            write!(f, "\nPORT={}", self.port_env_value())?;
+        }
+        write!(
+            f,
+r#"
+HTTP_PROTOCOLS={}
+HTTP_TLS={}
+LISTEN_BACKLOG={}
+TRAILING_SLASH={}"#,
+            self.protocols,
+            self.tls,
+            self.backlog,
+            self.trailing_slash,
+        )?;
+        if let Some(redirect_port) = self.redirect_port {
+            write!(f, "\nHTTP_REDIRECT_PORT={redirect_port}")?;
+        }
+        if let Some(socket_path) = &self.socket_path {
+            write!(f, "\n{}", quoted_env_line(UNIX_SOCKET_PATH, socket_path))?;
+        }
+        if let Some(read_timeout) = self.read_timeout {
+            write!(f, "\nREAD_TIMEOUT_SEC={}", read_timeout.as_secs())?;
+        }
+        if let Some(write_timeout) = self.write_timeout {
+            write!(f, "\nWRITE_TIMEOUT_SEC={}", write_timeout.as_secs())?;
+        }
+        Ok(())
     }
 }
@@ -0,0 +1,110 @@
+//! The [`TelemetryConfig`] struct represents settings used to export traces with OpenTelemetry.
+
+use crate::audit::var;
+use crate::conf::quoted_env_line;
+use crate::env_parsable;
+use crate::vars::{OTEL_EXPORTER_OTLP_ENDPOINT, OTEL_SERVICE_NAME, OTEL_TRACES_SAMPLER_ARG};
+use anyhow::{bail, Result};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+const DEFAULT_SERVICE_NAME: &str = "unknown_service";
+const DEFAULT_SAMPLE_RATIO: f64 = 1.0;
+
+/// Settings used to set up an OpenTelemetry (OTLP) exporter, read from
+/// the standard OTEL environment variables, so it works out of the box
+/// with existing infrastructure.
+#[derive(Debug, Clone)]
+pub struct TelemetryConfig {
+    /// OTLP exporter endpoint, set with the `OTEL_EXPORTER_OTLP_ENDPOINT` env
+    pub otlp_endpoint: Option<String>,
+    /// Name of the service reported in the traces, set with the `OTEL_SERVICE_NAME` env,
+    /// default "unknown_service"
+    pub service_name: String,
+    /// Ratio of traces sampled, from 0.0 to 1.0, set with the `OTEL_TRACES_SAMPLER_ARG` env,
+    /// default 1.0 (all traces are sampled)
+    pub sample_ratio: f64,
+}
+
+impl TelemetryConfig {
+    /// Init the object reading the OTEL environment variables, returning `None`
+    /// if `OTEL_EXPORTER_OTLP_ENDPOINT` is not set (telemetry disabled).
+    ///
+    /// # Examples
+    /// ```
+    /// use std::env;
+    /// use server_env_config::telemetry::TelemetryConfig;
+    ///
+    /// // No endpoint, no telemetry
+    /// assert!(TelemetryConfig::init().unwrap().is_none());
+    ///
+    /// env::set_var("OTEL_EXPORTER_OTLP_ENDPOINT", "http://localhost:4317");
+    /// env::set_var("OTEL_SERVICE_NAME", "my-api");
+    /// let telemetry = TelemetryConfig::init().unwrap().unwrap();
+    /// assert_eq!(telemetry.otlp_endpoint.as_deref(), Some("http://localhost:4317"));
+    /// assert_eq!(telemetry.service_name, "my-api");
+    /// assert_eq!(telemetry.sample_ratio, 1.0);
+    ///
+    /// // The sample ratio must be between 0 and 1
+    /// env::set_var("OTEL_TRACES_SAMPLER_ARG", "1.5");
+    /// assert!(TelemetryConfig::init().is_err());
+    /// ```
+    pub fn init() -> Result<Option<Self>> {
+        let otlp_endpoint = match var(OTEL_EXPORTER_OTLP_ENDPOINT) {
+            Ok(endpoint) if !endpoint.is_empty() => endpoint,
+            _ => return Ok(None),
+        };
+        let service_name = var(OTEL_SERVICE_NAME).unwrap_or(DEFAULT_SERVICE_NAME.to_string());
+        let sample_ratio = env_parsable::<f64>(OTEL_TRACES_SAMPLER_ARG, DEFAULT_SAMPLE_RATIO)?;
+        if !(0.0..=1.0).contains(&sample_ratio) {
+            bail!("OTEL_TRACES_SAMPLER_ARG must be between 0 and 1, got \"{sample_ratio}\"");
+        }
+        Ok(Some(TelemetryConfig {
+            otlp_endpoint: Some(otlp_endpoint),
+            service_name,
+            sample_ratio,
+        }))
+    }
+
+    /// Get all the config values in a map, using as key the environment
+    /// variable used to set-up each value, even if the value was set
+    /// in another way, e.g. using a default value.
+    pub fn to_env_map(&self) -> BTreeMap<String, String> {
+        let mut map = BTreeMap::from([
+            (OTEL_SERVICE_NAME.to_string(), self.service_name.clone()),
+            (OTEL_TRACES_SAMPLER_ARG.to_string(), self.sample_ratio.to_string()),
+        ]);
+        if let Some(endpoint) = &self.otlp_endpoint {
+            map.insert(OTEL_EXPORTER_OTLP_ENDPOINT.to_string(), endpoint.clone());
+        }
+        map
+    }
+
+    /// Get the default values of the settings that have one, in the
+    /// same format than [`TelemetryConfig::to_env_map()`].
+    pub(crate) fn default_env_map() -> BTreeMap<String, String> {
+        BTreeMap::from([
+            (OTEL_SERVICE_NAME.to_string(), DEFAULT_SERVICE_NAME.to_string()),
+            (OTEL_TRACES_SAMPLER_ARG.to_string(), DEFAULT_SAMPLE_RATIO.to_string()),
+        ])
+    }
+}
+
+impl Display for TelemetryConfig {
+    /// This `to_string()` implementation prints out all the config
+    /// values in `.env` format, using as key the environment variable
+    /// used to set-up the config, even if the configuration was
+    /// set in another way, e.g. using a default value.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+r#"{}
+{}
+OTEL_TRACES_SAMPLER_ARG={}"#,
+            quoted_env_line(OTEL_EXPORTER_OTLP_ENDPOINT, self.otlp_endpoint.as_deref().unwrap_or_default()),
+            quoted_env_line(OTEL_SERVICE_NAME, &self.service_name),
+            self.sample_ratio,
+        )
+    }
+}
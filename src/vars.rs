@@ -0,0 +1,219 @@
+//! Names of the environment variables read by the crate.
+
+/// Deployment environment, see [`Environment::init()`](crate::env::Environment::init).
+pub const APP_ENV: &str = "APP_ENV";
+/// Host of the Kubernetes API, set by Kubernetes in every container,
+/// see [`in_container()`](crate::env::in_container).
+pub const KUBERNETES_SERVICE_HOST: &str = "KUBERNETES_SERVICE_HOST";
+/// Format of the application logs.
+pub const LOG_FORMAT: &str = "LOG_FORMAT";
+/// Filter directive of the logs, see [`Config::rust_log_directive()`](crate::Config::rust_log_directive).
+pub const RUST_LOG: &str = "RUST_LOG";
+/// Name of the application.
+pub const APP_NAME: &str = "APP_NAME";
+/// Version of the application.
+pub const APP_VERSION: &str = "APP_VERSION";
+/// Whether the application is in maintenance mode.
+pub const MAINTENANCE_MODE: &str = "MAINTENANCE_MODE";
+/// Comma-separated hosts accepted in the `Host` header, `*` to accept any.
+pub const ALLOWED_HOSTS: &str = "ALLOWED_HOSTS";
+/// Prefix of the feature flags, e.g. `FEATURE_NEW_CHECKOUT`,
+/// see [`Config::features`](crate::Config::features).
+pub const FEATURE_PREFIX: &str = "FEATURE_";
+/// Disables the colored output when set, see <https://no-color.org>.
+pub const NO_COLOR: &str = "NO_COLOR";
+/// Forces the colored output when set, even if the output is not a terminal.
+pub const FORCE_COLOR: &str = "FORCE_COLOR";
+/// Host address of the HTTP server.
+pub const HOST: &str = "HOST";
+/// Alias of [`HOST`], read if `HOST` is not set.
+pub const BIND_ADDRESS: &str = "BIND_ADDRESS";
+/// Alias of [`HOST`], read if `HOST` and [`BIND_ADDRESS`] are not set.
+pub const LISTEN_ADDRESS: &str = "LISTEN_ADDRESS";
+/// Aliases of [`HOST`] read by default, in order of precedence.
+pub const HOST_ALIASES: &[&str] = &[BIND_ADDRESS, LISTEN_ADDRESS];
+/// Port of the HTTP server.
+pub const PORT: &str = "PORT";
+/// API URI of the HTTP server.
+pub const APP_URI: &str = "APP_URI";
+/// HTTP protocol versions accepted by the HTTP server.
+pub const HTTP_PROTOCOLS: &str = "HTTP_PROTOCOLS";
+/// Whether the HTTP server is reached with TLS (HTTPS).
+pub const HTTP_TLS: &str = "HTTP_TLS";
+/// Port of the plain-text HTTP redirect server.
+pub const HTTP_REDIRECT_PORT: &str = "HTTP_REDIRECT_PORT";
+/// Max length of the queue of pending connections of the HTTP server.
+pub const LISTEN_BACKLOG: &str = "LISTEN_BACKLOG";
+/// Whether the URL of the HTTP server ends with a slash.
+pub const TRAILING_SLASH: &str = "TRAILING_SLASH";
+/// Path of the Unix socket the HTTP server listens on, instead of a TCP port.
+pub const UNIX_SOCKET_PATH: &str = "UNIX_SOCKET_PATH";
+/// Max time to wait reading from a connection of the HTTP server, in seconds.
+pub const READ_TIMEOUT_SEC: &str = "READ_TIMEOUT_SEC";
+/// Max time to wait writing to a connection of the HTTP server, in seconds.
+pub const WRITE_TIMEOUT_SEC: &str = "WRITE_TIMEOUT_SEC";
+/// Process ID the systemd socket activation is meant for.
+pub const LISTEN_PID: &str = "LISTEN_PID";
+/// Number of file descriptors passed by systemd socket activation.
+pub const LISTEN_FDS: &str = "LISTEN_FDS";
+/// Database string connection, may be overridden per environment
+/// with the `DATABASE_URL_{ENV}` variables, e.g. `DATABASE_URL_PRODUCTION`.
+pub const DATABASE_URL: &str = "DATABASE_URL";
+/// Database string connections of the shards, separated by commas,
+/// see [`DbConfig::init_many()`](crate::db::DbConfig::init_many).
+pub const DATABASE_URLS: &str = "DATABASE_URLS";
+/// Database string connection of a read replica.
+pub const DATABASE_REPLICA_URL: &str = "DATABASE_REPLICA_URL";
+/// Scheme of the database string connection assembled from the `DB_*`
+/// components, when [`DATABASE_URL`] is not set.
+pub const DB_SCHEME: &str = "DB_SCHEME";
+/// Database host, used to assemble the string connection when
+/// [`DATABASE_URL`] is not set.
+pub const DB_HOST: &str = "DB_HOST";
+/// Database port, used to assemble the string connection.
+pub const DB_PORT: &str = "DB_PORT";
+/// Database user, used to assemble the string connection.
+pub const DB_USER: &str = "DB_USER";
+/// Database password, used to assemble the string connection.
+pub const DB_PASSWORD: &str = "DB_PASSWORD";
+/// Database name, used to assemble the string connection.
+pub const DB_NAME: &str = "DB_NAME";
+/// Name of the database connection pool.
+pub const DB_POOL_NAME: &str = "DB_POOL_NAME";
+/// Query used to test the database connections.
+pub const DB_TEST_QUERY: &str = "DB_TEST_QUERY";
+/// Whether the app must only read from the database.
+pub const DB_READ_ONLY: &str = "DB_READ_ONLY";
+/// Time after which the database statements are logged as slow, in milliseconds.
+pub const DB_SLOW_STATEMENT_MS: &str = "DB_SLOW_STATEMENT_MS";
+/// Character set of the database connections, e.g. `utf8mb4`.
+pub const DB_CHARSET: &str = "DB_CHARSET";
+/// Time zone of the database connection sessions, e.g. `UTC`.
+pub const DB_TIMEZONE: &str = "DB_TIMEZONE";
+/// Order in which the idle database connections are acquired, `fifo` or `lifo`.
+pub const DB_ACQUIRE_ORDER: &str = "DB_ACQUIRE_ORDER";
+/// Whether to allow database connections without TLS in production.
+pub const ALLOW_INSECURE_DB: &str = "ALLOW_INSECURE_DB";
+/// Whether to log the resolved database pool settings at start-up.
+pub const DB_LOG_SUMMARY: &str = "DB_LOG_SUMMARY";
+/// Min database connections created at start-up.
+pub const MIN_CONNECTIONS: &str = "MIN_CONNECTIONS";
+/// Whether to accept invalid SSL certificates from the database server.
+pub const DB_ACCEPT_INVALID_CERTS: &str = "DB_ACCEPT_INVALID_CERTS";
+/// Max database connections allowed.
+pub const MAX_CONNECTIONS: &str = "MAX_CONNECTIONS";
+/// Time allowed to acquire a database connection, in milliseconds.
+pub const ACQUIRE_TIMEOUT_MS: &str = "ACQUIRE_TIMEOUT_MS";
+/// Time allowed to acquire a database connection, in human form (e.g. `750ms`),
+/// takes precedence over [`ACQUIRE_TIMEOUT_MS`].
+pub const ACQUIRE_TIMEOUT: &str = "ACQUIRE_TIMEOUT";
+/// Max times to retry acquiring a database connection.
+pub const ACQUIRE_MAX_RETRIES: &str = "ACQUIRE_MAX_RETRIES";
+/// Time to wait between retries to acquire a database connection, in milliseconds.
+pub const ACQUIRE_RETRY_DELAY_MS: &str = "ACQUIRE_RETRY_DELAY_MS";
+/// Time to wait between retries to acquire a database connection, in human
+/// form (e.g. `500ms`), takes precedence over [`ACQUIRE_RETRY_DELAY_MS`].
+pub const ACQUIRE_RETRY_DELAY: &str = "ACQUIRE_RETRY_DELAY";
+/// Max time a database connection can be idle, in seconds.
+pub const IDLE_TIMEOUT_SEC: &str = "IDLE_TIMEOUT_SEC";
+/// Max time a database connection can be idle, in human form (e.g. `5m`),
+/// takes precedence over [`IDLE_TIMEOUT_SEC`].
+pub const IDLE_TIMEOUT: &str = "IDLE_TIMEOUT";
+/// Whether to test database connections before acquiring them.
+pub const TEST_BEFORE_ACQUIRE: &str = "TEST_BEFORE_ACQUIRE";
+/// OpenTelemetry exporter endpoint.
+pub const OTEL_EXPORTER_OTLP_ENDPOINT: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+/// OpenTelemetry service name.
+pub const OTEL_SERVICE_NAME: &str = "OTEL_SERVICE_NAME";
+/// OpenTelemetry traces sample ratio.
+pub const OTEL_TRACES_SAMPLER_ARG: &str = "OTEL_TRACES_SAMPLER_ARG";
+
+/// Environment variables required to run a "full" server, see
+/// [`Config::missing_required()`](crate::Config::missing_required).
+pub const REQUIRED: &[&str] = &[DATABASE_URL];
+
+/// Environment variables whose new values can be applied without restarting
+/// the app when the config is reloaded, see
+/// [`Config::reloadable_fields()`](crate::Config::reloadable_fields).
+pub const RELOADABLE: &[&str] = &[
+    LOG_FORMAT,
+    RUST_LOG,
+    MAINTENANCE_MODE,
+    ALLOWED_HOSTS,
+    READ_TIMEOUT_SEC,
+    WRITE_TIMEOUT_SEC,
+    MIN_CONNECTIONS,
+    MAX_CONNECTIONS,
+    ACQUIRE_TIMEOUT_MS,
+    ACQUIRE_TIMEOUT,
+    ACQUIRE_MAX_RETRIES,
+    ACQUIRE_RETRY_DELAY_MS,
+    ACQUIRE_RETRY_DELAY,
+    IDLE_TIMEOUT_SEC,
+    IDLE_TIMEOUT,
+    TEST_BEFORE_ACQUIRE,
+    DB_SLOW_STATEMENT_MS,
+    OTEL_TRACES_SAMPLER_ARG,
+];
+
+/// All the environment variables read by the crate, except the
+/// per-environment `DATABASE_URL_{ENV}` variables.
+pub const ALL: &[&str] = &[
+    APP_ENV,
+    KUBERNETES_SERVICE_HOST,
+    LOG_FORMAT,
+    RUST_LOG,
+    APP_NAME,
+    APP_VERSION,
+    MAINTENANCE_MODE,
+    ALLOWED_HOSTS,
+    NO_COLOR,
+    FORCE_COLOR,
+    HOST,
+    BIND_ADDRESS,
+    LISTEN_ADDRESS,
+    PORT,
+    APP_URI,
+    HTTP_PROTOCOLS,
+    HTTP_TLS,
+    HTTP_REDIRECT_PORT,
+    LISTEN_BACKLOG,
+    TRAILING_SLASH,
+    UNIX_SOCKET_PATH,
+    READ_TIMEOUT_SEC,
+    WRITE_TIMEOUT_SEC,
+    LISTEN_PID,
+    LISTEN_FDS,
+    DATABASE_URL,
+    DATABASE_URLS,
+    DATABASE_REPLICA_URL,
+    DB_SCHEME,
+    DB_HOST,
+    DB_PORT,
+    DB_USER,
+    DB_PASSWORD,
+    DB_NAME,
+    DB_POOL_NAME,
+    MIN_CONNECTIONS,
+    MAX_CONNECTIONS,
+    ACQUIRE_TIMEOUT_MS,
+    ACQUIRE_TIMEOUT,
+    ACQUIRE_MAX_RETRIES,
+    ACQUIRE_RETRY_DELAY_MS,
+    ACQUIRE_RETRY_DELAY,
+    IDLE_TIMEOUT_SEC,
+    IDLE_TIMEOUT,
+    TEST_BEFORE_ACQUIRE,
+    DB_TEST_QUERY,
+    DB_ACCEPT_INVALID_CERTS,
+    ALLOW_INSECURE_DB,
+    DB_LOG_SUMMARY,
+    DB_READ_ONLY,
+    DB_SLOW_STATEMENT_MS,
+    DB_CHARSET,
+    DB_TIMEZONE,
+    DB_ACQUIRE_ORDER,
+    OTEL_EXPORTER_OTLP_ENDPOINT,
+    OTEL_SERVICE_NAME,
+    OTEL_TRACES_SAMPLER_ARG,
+];
@@ -0,0 +1,104 @@
+//! The [`ConfigWatcher`] struct watches a `.env` file to reload the configuration.
+
+use crate::dotenv;
+use crate::Config;
+use anyhow::{Context, Result};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::Path;
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+/// Time to wait for more changes in the file before reloading it,
+/// so successive writes trigger only one reload.
+const DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// Handle of a `.env` file watched with [`Config::watch()`],
+/// the file stops being watched when the handle is dropped.
+pub struct ConfigWatcher {
+    _watcher: RecommendedWatcher,
+}
+
+impl Config {
+    /// Watch the `.env` file at `path`, and each time it changes, load its
+    /// variables in the process environment (overriding the ones set, see
+    /// [`dotenv::load_override()`]) and initialize the config again with
+    /// [`Config::init()`], passing the result to `callback`, that is called
+    /// from a background thread. Rapid successive writes only trigger one reload.
+    ///
+    /// The file is watched until the [`ConfigWatcher`] returned is dropped.
+    /// The reloads read the process environment, even if the watch is started
+    /// within [`with_source()`](crate::source::with_source).
+    ///
+    /// Note that this crate only reads the configuration, applying the new
+    /// values is up to the `callback`: settings like the log format, the
+    /// telemetry sampling or the pool timeouts may be applied without restarting,
+    /// but the values used to bind the server (`HOST`, `PORT`) or connect
+    /// to the database (`DATABASE_URL`) require a restart, see
+    /// [`Config::reloadable_fields()`].
+    ///
+    /// Only available with the `watch` feature.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::env;
+    /// use std::fs;
+    /// use std::sync::mpsc::channel;
+    /// use std::time::Duration;
+    /// use server_env_config::Config;
+    ///
+    /// let path = env::temp_dir().join("server_env_config_watch.env");
+    /// fs::write(&path, "PORT=8080\n").unwrap();
+    ///
+    /// let (tx, rx) = channel();
+    /// let watcher = Config::watch(&path, 9999, move |config| {
+    ///     tx.send(config.unwrap().server.port).unwrap();
+    /// }).unwrap();
+    ///
+    /// fs::write(&path, "PORT=9000\n").unwrap();
+    /// assert_eq!(rx.recv_timeout(Duration::from_secs(10)).unwrap(), 9000);
+    /// drop(watcher);
+    /// # fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn watch<F>(path: impl AsRef<Path>, default_port: u16, mut callback: F) -> Result<ConfigWatcher>
+    where
+        F: FnMut(Result<Config>) + Send + 'static,
+    {
+        let path = path.as_ref().to_path_buf();
+        let file_name = path
+            .file_name()
+            .with_context(|| format!("Invalid .env file path \"{}\"", path.display()))?
+            .to_owned();
+        // The directory is watched instead of the file, because many
+        // editors save files replacing them with a new one
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => Path::new(".").to_path_buf(),
+        };
+        let (tx, rx) = channel();
+        let mut watcher = notify::recommended_watcher(tx)?;
+        watcher
+            .watch(&dir, RecursiveMode::NonRecursive)
+            .with_context(|| format!("Failed to watch .env file \"{}\"", path.display()))?;
+        thread::spawn(move || {
+            while let Ok(event) = rx.recv() {
+                let changed = match event {
+                    Ok(event) => event.paths.iter().any(|p| p.file_name() == Some(&file_name)),
+                    Err(_) => false,
+                };
+                if !changed {
+                    continue;
+                }
+                loop {
+                    match rx.recv_timeout(DEBOUNCE) {
+                        Ok(_) => continue,
+                        Err(RecvTimeoutError::Timeout) => break,
+                        Err(RecvTimeoutError::Disconnected) => return,
+                    }
+                }
+                callback(dotenv::load_override(&path).and_then(|_| Config::init(default_port)));
+            }
+        });
+        Ok(ConfigWatcher { _watcher: watcher })
+    }
+}